@@ -0,0 +1,152 @@
+use wasm_bindgen::prelude::*;
+use web_sys::{CanvasRenderingContext2d, ImageData};
+
+use crate::{Cell, Coord, Universe, UniverseError};
+
+/// Pixel color of the ant itself.
+const ANT_COLOR: u32 = 0x00_FF_00_FF;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum Direction {
+    N,
+    E,
+    S,
+    W,
+}
+
+impl Direction {
+    fn right(self) -> Self {
+        match self {
+            Direction::N => Direction::E,
+            Direction::E => Direction::S,
+            Direction::S => Direction::W,
+            Direction::W => Direction::N,
+        }
+    }
+
+    fn left(self) -> Self {
+        match self {
+            Direction::N => Direction::W,
+            Direction::W => Direction::S,
+            Direction::S => Direction::E,
+            Direction::E => Direction::N,
+        }
+    }
+}
+
+/// Langton's Ant, walking over a Universe.
+#[wasm_bindgen]
+pub struct LangtonsAnt {
+    universe: Universe,
+    x: usize,
+    y: usize,
+    direction: Direction,
+}
+
+#[wasm_bindgen]
+impl LangtonsAnt {
+    /// Create an all-dead grid with the ant at the center, facing north.
+    /// Fails if either dimension is zero or the grid would exceed `MAX_CELLS`.
+    pub fn new(width: usize, height: usize) -> Result<LangtonsAnt, UniverseError> {
+        Ok(LangtonsAnt {
+            universe: Universe::try_new(width, height)?,
+            x: width / 2,
+            y: height / 2,
+            direction: Direction::N,
+        })
+    }
+
+    pub fn get_x(&self) -> usize {
+        self.x
+    }
+    pub fn get_y(&self) -> usize {
+        self.y
+    }
+
+    /// Advance the ant by one step.
+    pub fn step(&mut self) {
+        let here = Coord {
+            y: self.y,
+            x: self.x,
+        };
+        if self.universe[here].is_live() {
+            self.direction = self.direction.left();
+            self.universe.put(here, Cell::Dead);
+        } else {
+            self.direction = self.direction.right();
+            self.universe.put(here, Cell::Alive);
+        }
+
+        let (width, height) = (self.universe.width, self.universe.height);
+        match self.direction {
+            Direction::N => self.y = (self.y + height - 1) % height,
+            Direction::S => self.y = (self.y + 1) % height,
+            Direction::E => self.x = (self.x + 1) % width,
+            Direction::W => self.x = (self.x + width - 1) % width,
+        }
+    }
+
+    /// Advance the ant by `n` steps.
+    pub fn step_n(&mut self, n: usize) {
+        for _ in 0..n {
+            self.step();
+        }
+    }
+
+    /// Render the grid, with the ant drawn in green.
+    pub fn render2d(&self, canvas: &CanvasRenderingContext2d) -> Result<(), JsValue> {
+        let mut pixels = self.universe.pixels();
+        let offset = (self.y * self.universe.width + self.x) * 4;
+        pixels[offset..offset + 4].copy_from_slice(&ANT_COLOR.to_be_bytes());
+        let data = ImageData::new_with_u8_clamped_array_and_sh(
+            wasm_bindgen::Clamped(&pixels),
+            self.universe.width as u32,
+            self.universe.height as u32,
+        )?;
+        canvas.put_image_data(&data, 0.0, 0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_rejects_empty_grid() {
+        assert!(LangtonsAnt::new(0, 10).is_err());
+    }
+
+    #[test]
+    fn first_steps_turn_right_on_dead_cells() {
+        let mut ant = LangtonsAnt::new(10, 10).unwrap();
+        ant.step();
+        assert_eq!((ant.get_x(), ant.get_y()), (6, 5));
+        assert!(ant.universe[Coord { y: 5, x: 5 }].is_live());
+        ant.step_n(3);
+        // Four right turns bring the ant back to where it started.
+        assert_eq!((ant.get_x(), ant.get_y()), (5, 5));
+        assert_eq!(ant.universe.live_cell_count(), 4);
+    }
+
+    #[test]
+    fn builds_highway_after_ten_thousand_steps() {
+        // On the highway the ant repeats a 104-step cycle that moves it two
+        // cells diagonally and leaves 12 more live cells behind.
+        const PERIOD: usize = 104;
+        let mut ant = LangtonsAnt::new(200, 200).unwrap();
+        ant.step_n(11_000);
+        let mut previous = (ant.get_x() as isize, ant.get_y() as isize);
+        let mut previous_live = ant.universe.live_cell_count();
+        let mut displacement = None;
+        for _ in 0..5 {
+            ant.step_n(PERIOD);
+            let here = (ant.get_x() as isize, ant.get_y() as isize);
+            let moved = (here.0 - previous.0, here.1 - previous.1);
+            assert_eq!((moved.0.abs(), moved.1.abs()), (2, 2));
+            assert_eq!(*displacement.get_or_insert(moved), moved);
+            assert_eq!(ant.universe.live_cell_count(), previous_live + 12);
+            previous = here;
+            previous_live = ant.universe.live_cell_count();
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{Op, Universe};
+
+/// Universes with fewer cells than this are initialized without yielding.
+const ASYNC_THRESHOLD: usize = 256 * 256;
+
+/// Roughly how many cells to fill between yields to the event loop.
+const CHUNK_CELLS: usize = 64 * 1024;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Create a randomized Universe, as `new` followed by `randomize(seed)`,
+    /// yielding to the event loop periodically so large universes don't
+    /// freeze the page. Resolves with the Universe, or rejects if the
+    /// dimensions are too large. Small universes resolve immediately.
+    pub fn new_async(width: usize, height: usize, seed: u64) -> js_sys::Promise {
+        Universe::new_async_with_progress(width, height, seed, None)
+    }
+
+    /// As `new_async`, calling `progress` with the fraction completed,
+    /// from 0 to 1, after each chunk.
+    #[wasm_bindgen(js_name = new_async_with_progress)]
+    pub fn new_async_with_progress_js(
+        width: usize,
+        height: usize,
+        seed: u64,
+        progress: js_sys::Function,
+    ) -> js_sys::Promise {
+        Universe::new_async_with_progress(width, height, seed, Some(progress))
+    }
+}
+
+impl Universe {
+    fn new_async_with_progress(
+        width: usize,
+        height: usize,
+        seed: u64,
+        progress: Option<js_sys::Function>,
+    ) -> js_sys::Promise {
+        let mut universe = match Universe::try_new(width, height) {
+            Ok(universe) => universe,
+            Err(err) => return js_sys::Promise::reject(&err.into()),
+        };
+        if width * height < ASYNC_THRESHOLD {
+            universe.randomize(seed);
+            if let Some(progress) = &progress {
+                let _ = progress.call1(&JsValue::NULL, &JsValue::from(1.0));
+            }
+            return js_sys::Promise::resolve(&JsValue::from(universe));
+        }
+
+        wasm_bindgen_futures::future_to_promise(async move {
+            universe.record(Op::Randomize { seed });
+            let bits = universe.random_bits(seed);
+            let rows_per_chunk = (CHUNK_CELLS / width).max(1);
+            let mut y = 0;
+            while y < height {
+                let end = (y + rows_per_chunk).min(height);
+                universe.fill_random_rows(&bits, y..end);
+                y = end;
+                if let Some(progress) = &progress {
+                    progress.call1(&JsValue::NULL, &JsValue::from(y as f64 / height as f64))?;
+                }
+                yield_to_event_loop().await?;
+            }
+            universe.finish_randomize();
+            Ok(universe.into())
+        })
+    }
+}
+
+/// Resolve after a `setTimeout(0)`, letting the browser handle pending events.
+/// Outside a window, resolves on the next microtask instead.
+async fn yield_to_event_loop() -> Result<(), JsValue> {
+    let promise = match web_sys::window() {
+        Some(window) => js_sys::Promise::new(&mut |resolve, reject| {
+            if let Err(err) = window.set_timeout_with_callback(&resolve) {
+                let _ = reject.call1(&JsValue::NULL, &err);
+            }
+        }),
+        None => js_sys::Promise::resolve(&JsValue::UNDEFINED),
+    };
+    JsFuture::from(promise).await?;
+    Ok(())
+}
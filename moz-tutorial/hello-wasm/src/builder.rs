@@ -0,0 +1,207 @@
+use std::convert::TryFrom;
+
+use js_sys::Reflect;
+use wasm_bindgen::prelude::*;
+
+use crate::rule::Rule;
+use crate::topology::Topology;
+use crate::{Cell, Coord, SuperimposeMode, Universe, UniverseError};
+
+/// Dimension used when neither the builder nor an RLE seed specifies one.
+const DEFAULT_DIMENSION: usize = 64;
+
+/// Fluent construction of a Universe; see `Universe::builder`.
+///
+/// Seeds are applied in order: random fill, then the RLE pattern
+/// (centered), then individual cells.
+#[derive(Debug, Default, Clone)]
+pub struct UniverseBuilder {
+    width: Option<usize>,
+    height: Option<usize>,
+    rule: Option<String>,
+    topology: Option<String>,
+    seed_random: Option<u64>,
+    seed_rle: Option<String>,
+    seed_cells: Vec<(usize, usize)>,
+    undo_capacity: usize,
+    track_age: bool,
+}
+
+impl UniverseBuilder {
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: usize) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn rule(mut self, rule: &str) -> Self {
+        self.rule = Some(rule.to_owned());
+        self
+    }
+
+    pub fn topology(mut self, topology: &str) -> Self {
+        self.topology = Some(topology.to_owned());
+        self
+    }
+
+    pub fn seed_random(mut self, seed: u64) -> Self {
+        self.seed_random = Some(seed);
+        self
+    }
+
+    pub fn seed_rle(mut self, rle: &str) -> Self {
+        self.seed_rle = Some(rle.to_owned());
+        self
+    }
+
+    pub fn seed_cells(mut self, coords: &[(usize, usize)]) -> Self {
+        self.seed_cells.extend_from_slice(coords);
+        self
+    }
+
+    pub fn undo_capacity(mut self, capacity: usize) -> Self {
+        self.undo_capacity = capacity;
+        self
+    }
+
+    pub fn track_age(mut self, enabled: bool) -> Self {
+        self.track_age = enabled;
+        self
+    }
+
+    pub fn build(self) -> Result<Universe, UniverseError> {
+        let pattern = match &self.seed_rle {
+            Some(rle) => Some(
+                Universe::from_rle(rle)
+                    .map_err(|err| UniverseError::InvalidPattern(err.to_string()))?,
+            ),
+            None => None,
+        };
+        let width = self
+            .width
+            .or_else(|| pattern.as_ref().map(|p| p.width))
+            .unwrap_or(DEFAULT_DIMENSION);
+        let height = self
+            .height
+            .or_else(|| pattern.as_ref().map(|p| p.height))
+            .unwrap_or(DEFAULT_DIMENSION);
+
+        let mut universe = Universe::try_new(width, height)?;
+        if let Some(rule) = &self.rule {
+            universe.rule = Rule::parse(rule)?;
+        } else if let Some(pattern) = &pattern {
+            universe.rule = pattern.rule.clone();
+        }
+        if let Some(topology) = &self.topology {
+            universe.topology = Topology::parse(topology)?;
+        }
+
+        if let Some(seed) = self.seed_random {
+            universe.randomize(seed);
+        }
+        if let Some(pattern) = &pattern {
+            let dx = (width as i32 - pattern.width as i32) / 2;
+            let dy = (height as i32 - pattern.height as i32) / 2;
+            universe.superimpose(pattern, dx, dy, SuperimposeMode::Or)?;
+        }
+        for &(x, y) in self.seed_cells.iter() {
+            universe.put(Coord { y, x }, Cell::Alive);
+        }
+
+        universe.set_undo_capacity(self.undo_capacity);
+        universe.set_track_age(self.track_age);
+        Ok(universe)
+    }
+}
+
+/// Read `key` from a JS object, treating `undefined` as absent.
+fn get_option(options: &JsValue, key: &str) -> Result<Option<JsValue>, JsValue> {
+    let value = Reflect::get(options, &JsValue::from_str(key))?;
+    Ok(if value.is_undefined() {
+        None
+    } else {
+        Some(value)
+    })
+}
+
+fn invalid_option(key: &str) -> JsValue {
+    js_sys::Error::new(&format!("invalid value for option {:?}", key)).into()
+}
+
+fn get_usize(options: &JsValue, key: &str) -> Result<Option<usize>, JsValue> {
+    get_option(options, key)?
+        .map(|v| {
+            v.as_f64()
+                .map(|n| n as usize)
+                .ok_or_else(|| invalid_option(key))
+        })
+        .transpose()
+}
+
+fn get_string(options: &JsValue, key: &str) -> Result<Option<String>, JsValue> {
+    get_option(options, key)?
+        .map(|v| v.as_string().ok_or_else(|| invalid_option(key)))
+        .transpose()
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Build a Universe from a plain JS options object, whose keys match
+    /// the `UniverseBuilder` methods: `width`, `height`, `rule`, `topology`,
+    /// `seed_random` (number or BigInt), `seed_rle`, `seed_cells`
+    /// (flat array of x, y pairs), `undo_capacity`, and `track_age`.
+    pub fn from_options(options: JsValue) -> Result<Universe, JsValue> {
+        let mut builder = Universe::builder();
+        if let Some(width) = get_usize(&options, "width")? {
+            builder = builder.width(width);
+        }
+        if let Some(height) = get_usize(&options, "height")? {
+            builder = builder.height(height);
+        }
+        if let Some(rule) = get_string(&options, "rule")? {
+            builder = builder.rule(&rule);
+        }
+        if let Some(topology) = get_string(&options, "topology")? {
+            builder = builder.topology(&topology);
+        }
+        if let Some(seed) = get_option(&options, "seed_random")? {
+            let seed = match seed.as_f64() {
+                Some(n) => n as u64,
+                None => u64::try_from(seed).map_err(|_| invalid_option("seed_random"))?,
+            };
+            builder = builder.seed_random(seed);
+        }
+        if let Some(rle) = get_string(&options, "seed_rle")? {
+            builder = builder.seed_rle(&rle);
+        }
+        if let Some(cells) = get_option(&options, "seed_cells")? {
+            let flat = cells
+                .dyn_into::<js_sys::Array>()
+                .map_err(|_| invalid_option("seed_cells"))?
+                .iter()
+                .map(|v| v.as_f64().map(|n| n as usize))
+                .collect::<Option<Vec<usize>>>()
+                .ok_or_else(|| invalid_option("seed_cells"))?;
+            let coords: Vec<(usize, usize)> = flat.chunks_exact(2).map(|p| (p[0], p[1])).collect();
+            builder = builder.seed_cells(&coords);
+        }
+        if let Some(capacity) = get_usize(&options, "undo_capacity")? {
+            builder = builder.undo_capacity(capacity);
+        }
+        if let Some(track_age) = get_option(&options, "track_age")? {
+            builder = builder.track_age(track_age.is_truthy());
+        }
+        Ok(builder.build()?)
+    }
+}
+
+impl Universe {
+    /// Start building a Universe; see `UniverseBuilder`.
+    pub fn builder() -> UniverseBuilder {
+        UniverseBuilder::default()
+    }
+}
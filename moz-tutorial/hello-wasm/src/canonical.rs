@@ -0,0 +1,194 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Coord, Universe};
+
+#[wasm_bindgen]
+impl Universe {
+    /// The live cells, cropped to their bounding box and in the one of their
+    /// 8 rotations and reflections with the smallest content, as a new Universe.
+    /// Patterns that are translations, rotations, or reflections of each other
+    /// normalize to the same Universe. An empty universe normalizes to 1x1.
+    pub fn normalize(&self) -> Universe {
+        let (x, y, width, height) = match self.bounding_box() {
+            Some(bounds) => bounds,
+            None => return self.blank_like(1, 1),
+        };
+        let live = |sx: usize, sy: usize| {
+            self[Coord {
+                y: y + sy,
+                x: x + sx,
+            }]
+            .is_live()
+        };
+        let transformed = |transform: usize| {
+            let (transpose, flip_x, flip_y) =
+                (transform & 4 != 0, transform & 2 != 0, transform & 1 != 0);
+            let (tw, th) = if transpose {
+                (height, width)
+            } else {
+                (width, height)
+            };
+            let mut content = Vec::with_capacity(tw * th);
+            for ty in 0..th {
+                for tx in 0..tw {
+                    let (mut sx, mut sy) = if transpose { (ty, tx) } else { (tx, ty) };
+                    if flip_x {
+                        sx = width - 1 - sx;
+                    }
+                    if flip_y {
+                        sy = height - 1 - sy;
+                    }
+                    content.push(live(sx, sy));
+                }
+            }
+            (content, tw, th)
+        };
+        let (content, tw, th) = (0..8)
+            .map(transformed)
+            .min()
+            .expect("there are 8 candidates");
+        let mut normal = self.blank_like(tw, th);
+        for (i, live) in content.into_iter().enumerate() {
+            if live {
+                normal.put(Coord::from_index(i, tw), Cell::Alive);
+            }
+        }
+        normal
+    }
+
+    /// Whether the live cells of the two universes are the same pattern,
+    /// up to translation, rotation, and reflection; see `normalize`.
+    pub fn is_equivalent_to(&self, other: &Universe) -> bool {
+        let (a, b) = (self.normalize(), other.normalize());
+        a.content_hash() == b.content_hash() && a.same_live_cells(&b)
+    }
+
+    /// A stable 64-bit FNV-1a hash of the dimensions and which cells are live.
+    pub fn content_hash(&self) -> u64 {
+        self.hash_from(FNV_OFFSET)
+    }
+
+    /// A 16-character URL-safe key for the pattern, equal for patterns that are
+    /// translations, rotations, or reflections of each other: 96 bits of the
+    /// hash of `normalize`, in unpadded base64url.
+    pub fn fingerprint(&self) -> String {
+        let normal = self.normalize();
+        let mut bytes = normal.content_hash().to_be_bytes().to_vec();
+        // A second hash with a different starting point, for 32 more bits.
+        bytes.extend_from_slice(&normal.hash_from(!FNV_OFFSET).to_be_bytes()[..4]);
+        base64url(&bytes)
+    }
+}
+
+/// The standard FNV-1a 64-bit offset basis.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Encode `bytes` as base64url, without padding.
+fn base64url(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut encoded = String::with_capacity((bytes.len() * 4).div_ceil(3));
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (i, &b)| group | ((b as u32) << (16 - 8 * i)));
+        for i in 0..=chunk.len() {
+            encoded.push(ALPHABET[(group >> (18 - 6 * i)) as usize & 63] as char);
+        }
+    }
+    encoded
+}
+
+impl Universe {
+    /// FNV-1a over the dimensions and which cells are live, starting from `offset`.
+    fn hash_from(&self, offset: u64) -> u64 {
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+        let dimensions = [self.width as u64, self.height as u64];
+        let bytes = dimensions
+            .iter()
+            .flat_map(|d| d.to_le_bytes())
+            .chain(self.content.iter().map(|cell| cell.is_live() as u8));
+        bytes.fold(offset, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(PRIME)
+        })
+    }
+
+    /// Whether both universes have the same dimensions and live cells.
+    fn same_live_cells(&self, other: &Universe) -> bool {
+        (self.width, self.height) == (other.width, other.height)
+            && self
+                .content
+                .iter()
+                .zip(other.content.iter())
+                .all(|(a, b)| a.is_live() == b.is_live())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The dimensions and live cells of `universe`'s canonical form.
+    fn canonical(universe: &Universe) -> (usize, usize, Vec<u32>) {
+        let normal = universe.normalize();
+        (normal.width, normal.height, normal.live_coords())
+    }
+
+    /// Every orientation of the glider, at several positions in a 10x10 universe.
+    fn gliders() -> Vec<Universe> {
+        let mut glider = Universe::from_pattern(&[".O.", "..O", "OOO"]).unwrap();
+        let mut gliders = Vec::new();
+        for offset in 0..4 {
+            for _ in 0..4 {
+                glider.rotate_cw().unwrap();
+                for flipped in [false, true] {
+                    let mut oriented = glider.scratch_copy();
+                    if flipped {
+                        oriented.flip_horizontal().unwrap();
+                    }
+                    let mut universe = Universe::new(10, 10);
+                    oriented.tile_into(&mut universe, 2 * offset, offset, 1, 1);
+                    gliders.push(universe);
+                }
+            }
+        }
+        gliders
+    }
+
+    #[test]
+    fn fingerprint_is_url_safe() {
+        let fingerprint = gliders()[0].fingerprint();
+        assert_eq!(fingerprint.len(), 16);
+        assert!(fingerprint
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[test]
+    fn fingerprint_ignores_symmetries() {
+        let gliders = gliders();
+        for glider in &gliders {
+            assert_eq!(glider.fingerprint(), gliders[0].fingerprint());
+        }
+        let blinker = Universe::from_pattern(&["OOO"]).unwrap();
+        assert_ne!(blinker.fingerprint(), gliders[0].fingerprint());
+    }
+
+    #[test]
+    fn equal_fingerprints_have_equal_canonical_forms() {
+        let mut patterns = gliders();
+        for seed in 0..32 {
+            let mut universe = Universe::new(6, 5);
+            universe.randomize(seed);
+            patterns.push(universe);
+        }
+        patterns.push(Universe::new(4, 4));
+        for a in &patterns {
+            for b in &patterns {
+                if a.fingerprint() == b.fingerprint() {
+                    assert_eq!(canonical(a), canonical(b));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::VecDeque;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{line, Coord, Universe};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Set the cells along the line from (x1, y1) to (x2, y2), inclusive,
+    /// live or dead.
+    pub fn draw_line(&mut self, x1: usize, y1: usize, x2: usize, y2: usize, alive: bool) {
+        let points = line(Coord { y: y1, x: x1 }, Coord { y: y2, x: x2 })
+            .into_iter()
+            .map(|here| (here.x as isize, here.y as isize));
+        self.draw(points, alive);
+    }
+
+    /// Set the cells on the border of the `w` x `h` rectangle at (x, y) live or
+    /// dead, wrapping around the edges.
+    pub fn draw_rect_outline(&mut self, x: usize, y: usize, w: usize, h: usize, alive: bool) {
+        if w == 0 || h == 0 {
+            return;
+        }
+        let (left, top) = (x as isize, y as isize);
+        let (right, bottom) = (left + w as isize - 1, top + h as isize - 1);
+        let horizontal = (left..=right).flat_map(|x| [(x, top), (x, bottom)]);
+        let vertical = (top..=bottom).flat_map(|y| [(left, y), (right, y)]);
+        self.draw(horizontal.chain(vertical), alive);
+    }
+
+    /// Set every cell in the `w` x `h` rectangle at (x, y) live or dead,
+    /// wrapping around the edges.
+    pub fn draw_filled_rect(&mut self, x: usize, y: usize, w: usize, h: usize, alive: bool) {
+        // Wider or taller than the universe would only cover cells again.
+        let (w, h) = (w.min(self.width), h.min(self.height));
+        let (left, top) = (x as isize, y as isize);
+        let points =
+            (top..top + h as isize).flat_map(|y| (left..left + w as isize).map(move |x| (x, y)));
+        self.draw(points, alive);
+    }
+
+    /// Set the cells on the circle of `radius` around (cx, cy) live or dead,
+    /// wrapping around the edges. A radius of 0 is just the center cell, and
+    /// one larger than both dimensions counts as the larger of them.
+    pub fn draw_circle_outline(&mut self, cx: usize, cy: usize, radius: usize, alive: bool) {
+        let radius = radius.min(self.width.max(self.height));
+        // The midpoint circle algorithm: step along one octant, mirroring each
+        // point into the other seven.
+        let (cx, cy) = (cx as isize, cy as isize);
+        let (mut x, mut y) = (radius as isize, 0);
+        let mut err = 1 - x;
+        let mut points = Vec::new();
+        while x >= y {
+            for (dx, dy) in [(x, y), (y, x)] {
+                for (sx, sy) in [(1, 1), (-1, 1), (1, -1), (-1, -1)] {
+                    points.push((cx + sx * dx, cy + sy * dy));
+                }
+            }
+            y += 1;
+            if err < 0 {
+                err += 2 * y + 1;
+            } else {
+                x -= 1;
+                err += 2 * (y - x) + 1;
+            }
+        }
+        self.draw(points.into_iter(), alive);
+    }
+
+    /// Bucket fill: set the region of `target`-state cells reachable from
+    /// (x, y) through edge neighbors, under the current topology, to `fill`.
+    /// Returns the number of cells changed: 0 if the cell at (x, y) isn't in
+    /// the `target` state, or if `target` and `fill` are the same.
+    pub fn flood_fill(&mut self, x: usize, y: usize, target: bool, fill: bool) -> usize {
+        let start = Coord {
+            y: y % self.height,
+            x: x % self.width,
+        };
+        if target == fill || self[start].is_live() != target {
+            return 0;
+        }
+        let mut reached = vec![false; self.content.len()];
+        reached[start.y * self.width + start.x] = true;
+        let mut pending = VecDeque::from([start]);
+        let mut coords = Vec::new();
+        while let Some(here) = pending.pop_front() {
+            coords.extend([here.x as u32, here.y as u32]);
+            let (hx, hy) = (here.x as isize, here.y as isize);
+            for (nx, ny) in [(hx - 1, hy), (hx + 1, hy), (hx, hy - 1), (hx, hy + 1)] {
+                let next = match self.topology.resolve(nx, ny, self.width, self.height) {
+                    Some(next) => next,
+                    None => continue,
+                };
+                let index = next.y * self.width + next.x;
+                if !reached[index] && self[next].is_live() == target {
+                    reached[index] = true;
+                    pending.push_back(next);
+                }
+            }
+        }
+        let filled = coords.len() / 2;
+        self.set_cells_bulk(coords, fill);
+        filled
+    }
+}
+
+impl Universe {
+    /// Set each (x, y) in `points`, wrapped onto the universe, live or dead.
+    fn draw(&mut self, points: impl Iterator<Item = (isize, isize)>, alive: bool) {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let coords = points
+            .flat_map(|(x, y)| [x.rem_euclid(width) as u32, y.rem_euclid(height) as u32])
+            .collect();
+        self.set_cells_bulk(coords, alive);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 7x7 universe with a hollow 5x5 square in the middle.
+    fn hollow_square() -> Universe {
+        Universe::from_pattern(&[
+            ".......", ".OOOOO.", ".O...O.", ".O...O.", ".O...O.", ".OOOOO.", ".......",
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn fills_only_inside_hollow_square() {
+        let mut universe = hollow_square();
+        assert_eq!(universe.flood_fill(3, 3, false, true), 9);
+        assert_eq!(universe.live_cell_count(), 25);
+        assert_eq!(universe.bounding_box(), Some((1, 1, 5, 5)));
+    }
+
+    #[test]
+    fn fills_ring_outside_square() {
+        let mut universe = hollow_square();
+        // The ring of 24 dead cells around the square.
+        assert_eq!(universe.flood_fill(0, 0, false, true), 24);
+        assert!(!universe[Coord { y: 3, x: 3 }].is_live());
+    }
+
+    #[test]
+    fn clears_connected_live_cells() {
+        let mut universe = hollow_square();
+        assert_eq!(universe.flood_fill(1, 1, true, false), 16);
+        assert_eq!(universe.live_cell_count(), 0);
+    }
+
+    #[test]
+    fn does_nothing_when_start_is_not_target_or_states_match() {
+        let mut universe = hollow_square();
+        assert_eq!(universe.flood_fill(1, 1, false, true), 0);
+        assert_eq!(universe.flood_fill(3, 3, false, false), 0);
+        assert_eq!(universe.live_cell_count(), 16);
+    }
+
+    #[test]
+    fn huge_filled_rect_covers_universe() {
+        let mut universe = Universe::new(6, 4);
+        universe.draw_filled_rect(2, 1, usize::MAX / 2, usize::MAX / 2, true);
+        assert_eq!(universe.live_cell_count(), 24);
+    }
+
+    #[test]
+    fn huge_circle_is_clamped() {
+        let mut huge = Universe::new(6, 4);
+        huge.draw_circle_outline(2, 1, usize::MAX, true);
+        let mut clamped = Universe::new(6, 4);
+        clamped.draw_circle_outline(2, 1, 6, true);
+        assert_eq!(huge.live_coords(), clamped.live_coords());
+    }
+}
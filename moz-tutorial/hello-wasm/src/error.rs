@@ -0,0 +1,158 @@
+use wasm_bindgen::JsValue;
+
+/// Errors from constructing or modifying a Universe.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum UniverseError {
+    /// The requested dimensions overflow, or exceed `MAX_CELLS`.
+    DimensionTooLarge { width: usize, height: usize },
+    /// The requested width or height is zero.
+    EmptyDimension { width: usize, height: usize },
+    /// A pattern had no rows, or only empty rows.
+    EmptyPattern,
+    /// A pattern contained a character that is neither a live nor a dead marker.
+    InvalidPatternChar {
+        row: usize,
+        column: usize,
+        found: char,
+    },
+    /// A rule string could not be parsed.
+    InvalidRule(String),
+    /// A topology name was not recognized.
+    InvalidTopology(String),
+    /// The operation is not meaningful under the universe's current topology.
+    UnsupportedTopology { operation: &'static str },
+    /// A resize anchor name was not recognized.
+    InvalidAnchor(String),
+    /// A mode name was not recognized.
+    InvalidMode(String),
+    /// A pattern does not fit within the universe.
+    PatternTooLarge,
+    /// A pattern could not be decoded.
+    InvalidPattern(String),
+    /// A log level name was not recognized.
+    InvalidLogLevel(String),
+    /// Cell connectivity must be 4 (edges only) or 8 (edges and corners).
+    InvalidConnectivity(u8),
+    /// A stencil had the wrong size or an unknown entry.
+    InvalidStencil(String),
+    /// A shared buffer does not match the universe's size.
+    SharedBufferMismatch { expected: usize, found: usize },
+    /// A search is outside the limits it supports.
+    UnsupportedSearch(String),
+    /// A stop condition could not be parsed.
+    InvalidStopCondition(String),
+    /// The operation needs age tracking, which is off; see `Universe::set_track_age`.
+    AgeTrackingRequired { operation: &'static str },
+}
+
+impl std::fmt::Display for UniverseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UniverseError::DimensionTooLarge { width, height } => write!(
+                f,
+                "{}x{} universe exceeds the limit of {} cells",
+                width,
+                height,
+                crate::MAX_CELLS
+            ),
+            UniverseError::EmptyDimension { width, height } => {
+                write!(f, "{}x{} universe has no cells", width, height)
+            }
+            UniverseError::EmptyPattern => write!(f, "pattern is empty"),
+            UniverseError::InvalidPatternChar { row, column, found } => write!(
+                f,
+                "unsupported character {:?} at row {}, column {}",
+                found, row, column
+            ),
+            UniverseError::InvalidRule(rule) => write!(f, "invalid rule {:?}", rule),
+            UniverseError::InvalidTopology(name) => write!(f, "unknown topology {:?}", name),
+            UniverseError::UnsupportedTopology { operation } => {
+                write!(
+                    f,
+                    "{} is not supported on a non-orientable topology",
+                    operation
+                )
+            }
+            UniverseError::InvalidAnchor(name) => write!(f, "unknown anchor {:?}", name),
+            UniverseError::InvalidMode(name) => write!(f, "unknown mode {:?}", name),
+            UniverseError::PatternTooLarge => write!(f, "pattern is larger than the universe"),
+            UniverseError::InvalidPattern(err) => write!(f, "invalid pattern: {}", err),
+            UniverseError::InvalidLogLevel(name) => write!(f, "unknown log level {:?}", name),
+            UniverseError::InvalidConnectivity(n) => {
+                write!(f, "connectivity must be 4 or 8, not {}", n)
+            }
+            UniverseError::InvalidStencil(err) => write!(f, "invalid stencil: {}", err),
+            UniverseError::SharedBufferMismatch { expected, found } => write!(
+                f,
+                "shared buffer has {} bytes, expected one per cell ({})",
+                found, expected
+            ),
+            UniverseError::UnsupportedSearch(err) => write!(f, "unsupported search: {}", err),
+            UniverseError::InvalidStopCondition(condition) => {
+                write!(f, "unknown stop condition {:?}", condition)
+            }
+            UniverseError::AgeTrackingRequired { operation } => {
+                write!(f, "{} needs age tracking enabled", operation)
+            }
+        }
+    }
+}
+
+impl std::error::Error for UniverseError {}
+
+impl From<UniverseError> for JsValue {
+    fn from(err: UniverseError) -> Self {
+        js_sys::Error::new(&err.to_string()).into()
+    }
+}
+
+/// Errors from decoding serialized data.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum ParseError {
+    /// The input was not valid JSON of the expected shape.
+    InvalidJson(String),
+    /// The input was not a valid RLE pattern.
+    InvalidRle(String),
+    /// The input was not a valid Life 1.06 pattern.
+    InvalidLife106(String),
+    /// The input was not a valid URL fragment; see `Universe::from_url_fragment`.
+    InvalidUrlFragment(String),
+    /// The input was not a PNG image of a readable QR code.
+    InvalidQrCode(String),
+    /// The input's format was not recognized.
+    UnsupportedFormat,
+    /// The input decoded, but could not be applied to a Universe.
+    Universe(UniverseError),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::InvalidJson(err) => write!(f, "invalid JSON: {}", err),
+            ParseError::InvalidRle(err) => write!(f, "invalid RLE: {}", err),
+            ParseError::InvalidLife106(err) => write!(f, "invalid Life 1.06: {}", err),
+            ParseError::InvalidUrlFragment(err) => write!(f, "invalid URL fragment: {}", err),
+            ParseError::InvalidQrCode(err) => write!(f, "invalid QR code: {}", err),
+            ParseError::UnsupportedFormat => write!(
+                f,
+                "unsupported pattern format; expected RLE (.rle), plaintext (.cells), \
+                 or Life 1.06 (.lif)"
+            ),
+            ParseError::Universe(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<UniverseError> for ParseError {
+    fn from(err: UniverseError) -> Self {
+        ParseError::Universe(err)
+    }
+}
+
+impl From<ParseError> for JsValue {
+    fn from(err: ParseError) -> Self {
+        js_sys::Error::new(&err.to_string()).into()
+    }
+}
@@ -0,0 +1,51 @@
+/// A complex number as (real, imaginary).
+pub(crate) type Complex = (f64, f64);
+
+/// Transform `data` in place with an iterative radix-2 FFT, or its inverse
+/// (including the 1/n scaling) if `inverse` is set. `data.len()` must be a
+/// power of two.
+pub(crate) fn fft(data: &mut [Complex], inverse: bool) {
+    let n = data.len();
+    debug_assert!(n.is_power_of_two());
+    // Bit-reversal permutation, so each butterfly pass works on adjacent runs.
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let angle = sign * 2.0 * std::f64::consts::PI / len as f64;
+        // Computed directly rather than by repeated multiplication, which
+        // accumulates rounding error over long transforms.
+        let twiddles: Vec<Complex> = (0..len / 2)
+            .map(|k| ((angle * k as f64).cos(), (angle * k as f64).sin()))
+            .collect();
+        for chunk in data.chunks_exact_mut(len) {
+            let (low, high) = chunk.split_at_mut(len / 2);
+            for ((a, b), twiddle) in low.iter_mut().zip(high).zip(&twiddles) {
+                let t = mul(*b, *twiddle);
+                *b = (a.0 - t.0, a.1 - t.1);
+                *a = (a.0 + t.0, a.1 + t.1);
+            }
+        }
+        len <<= 1;
+    }
+    if inverse {
+        for value in data.iter_mut() {
+            *value = (value.0 / n as f64, value.1 / n as f64);
+        }
+    }
+}
+
+fn mul(a: Complex, b: Complex) -> Complex {
+    (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
@@ -0,0 +1,239 @@
+use std::convert::TryFrom;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{Cell, Coord, ParseError, Universe, UniverseError};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Read a pattern file, such as one from an `<input type="file">`.
+    /// Resolves with a new Universe, or rejects if the format is not recognized;
+    /// see `Universe::from_text`.
+    pub fn from_file_blob(blob: &web_sys::Blob) -> js_sys::Promise {
+        let text = blob.text();
+        wasm_bindgen_futures::future_to_promise(async move {
+            let text = JsFuture::from(text).await?;
+            let text = text
+                .as_string()
+                .ok_or_else(|| js_sys::Error::new("file is not text"))?;
+            Ok(Universe::from_text(&text)?.into())
+        })
+    }
+
+    /// Parse a pattern, detecting its format: RLE, plaintext (.cells),
+    /// or Life 1.06 (.lif).
+    pub fn from_text(text: &str) -> Result<Universe, ParseError> {
+        let first = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .ok_or(ParseError::UnsupportedFormat)?;
+        if first.starts_with("#Life 1.06") {
+            Universe::from_life106(text)
+        } else if first.starts_with("#Life") {
+            Err(ParseError::UnsupportedFormat)
+        } else if first.starts_with('#') || first.starts_with('x') {
+            Universe::from_rle(text)
+        } else if first.starts_with('!') || first.chars().all(|c| c == '.' || c == 'O') {
+            Universe::from_plaintext(text)
+        } else {
+            Err(ParseError::UnsupportedFormat)
+        }
+    }
+
+    /// Parse a plaintext (.cells) pattern: `!` comment lines, then rows of
+    /// `.` (dead) and `O` (live).
+    pub fn from_plaintext(text: &str) -> Result<Universe, ParseError> {
+        let rows: Vec<&str> = text
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.starts_with('!'))
+            .collect();
+        Ok(Universe::from_pattern(&rows)?)
+    }
+
+    /// Parse a Life 1.06 (.lif) pattern: a `#Life 1.06` header, then one
+    /// `x y` pair of live cell coordinates per line.
+    /// The universe is just large enough to hold the live cells.
+    pub fn from_life106(text: &str) -> Result<Universe, ParseError> {
+        let invalid = |msg: &str| ParseError::InvalidLife106(msg.to_owned());
+        let mut cells = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace().map(str::parse::<i64>);
+            match (fields.next(), fields.next(), fields.next()) {
+                (Some(Ok(x)), Some(Ok(y)), None) => cells.push((x, y)),
+                _ => return Err(invalid("expected one x y pair per line")),
+            }
+        }
+
+        let min_x = cells
+            .iter()
+            .map(|(x, _)| *x)
+            .min()
+            .ok_or_else(|| invalid("no cells"))?;
+        let min_y = cells.iter().map(|(_, y)| *y).min().unwrap_or(0);
+        let max_x = cells.iter().map(|(x, _)| *x).max().unwrap_or(0);
+        let max_y = cells.iter().map(|(_, y)| *y).max().unwrap_or(0);
+        let span = |min: i64, max: i64| {
+            max.checked_sub(min)
+                .and_then(|span| span.checked_add(1))
+                .and_then(|span| usize::try_from(span).ok())
+                .ok_or_else(|| invalid("coordinates too far apart"))
+        };
+        let width = span(min_x, max_x)?;
+        let height = span(min_y, max_y)?;
+
+        let mut universe = Universe::try_new(width, height)?;
+        for (x, y) in cells {
+            universe.put(
+                Coord {
+                    y: (y - min_y) as usize,
+                    x: (x - min_x) as usize,
+                },
+                Cell::Alive,
+            );
+        }
+        Ok(universe)
+    }
+
+    /// See `Universe::from_image_data`; `data` is as from `getImageData`.
+    pub fn create_from_image_data(
+        data: js_sys::Uint8ClampedArray,
+        w: u32,
+        h: u32,
+        threshold: u8,
+    ) -> Result<Universe, UniverseError> {
+        Universe::from_image_data(&data.to_vec(), w as usize, h as usize, threshold)
+    }
+}
+
+impl Universe {
+    /// Create a Universe from `w` x `h` RGBA pixels, with cells live where the
+    /// pixel's luminance (0.299 R + 0.587 G + 0.114 B) is above `threshold`.
+    /// Alpha is ignored. Each dimension of the universe is the nearest power of
+    /// two to the image's, and the image is scaled to fit by sampling the
+    /// nearest pixel.
+    pub fn from_image_data(
+        data: &[u8],
+        w: usize,
+        h: usize,
+        threshold: u8,
+    ) -> Result<Universe, UniverseError> {
+        let expected = w.checked_mul(h).and_then(|pixels| pixels.checked_mul(4));
+        if expected != Some(data.len()) {
+            return Err(UniverseError::InvalidPattern(format!(
+                "{} bytes of image data for a {}x{} image",
+                data.len(),
+                w,
+                h
+            )));
+        }
+        let (width, height) = (nearest_power_of_two(w), nearest_power_of_two(h));
+        let mut universe = Universe::try_new(width, height)?;
+        if w == 0 || h == 0 {
+            return Ok(universe);
+        }
+        for y in 0..height {
+            for x in 0..width {
+                let pixel = ((y * h / height) * w + x * w / width) * 4;
+                let [r, g, b] = [data[pixel], data[pixel + 1], data[pixel + 2]].map(f64::from);
+                if 0.299 * r + 0.587 * g + 0.114 * b > threshold as f64 {
+                    universe.put(Coord { y, x }, Cell::Alive);
+                }
+            }
+        }
+        Ok(universe)
+    }
+}
+
+/// The power of two closest to `n`, rounding ties up; 1 for 0.
+fn nearest_power_of_two(n: usize) -> usize {
+    let above = n.max(1).next_power_of_two();
+    let below = above / 2;
+    if below > 0 && n - below < above - n {
+        below
+    } else {
+        above
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RGBA pixels for a `w` x `h` black image with a white square of side
+    /// `side` whose top-left corner is at (x, y).
+    fn white_square(w: usize, h: usize, x: usize, y: usize, side: usize) -> Vec<u8> {
+        (0..w * h)
+            .flat_map(|i| {
+                let (px, py) = (i % w, i / w);
+                let inside = (x..x + side).contains(&px) && (y..y + side).contains(&py);
+                let value = if inside { 255 } else { 0 };
+                [value, value, value, 255]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn imports_white_square_on_black() {
+        let data = white_square(8, 8, 2, 3, 4);
+        let universe = Universe::from_image_data(&data, 8, 8, 128).unwrap();
+        assert_eq!((universe.get_width(), universe.get_height()), (8, 8));
+        for (coord, cell) in &universe {
+            let inside = (2..6).contains(&coord.x) && (3..7).contains(&coord.y);
+            assert_eq!(cell.is_live(), inside, "{:?}", coord);
+        }
+    }
+
+    #[test]
+    fn luminance_must_exceed_threshold() {
+        // Pure green has luminance 0.587 * 255 = 149.7.
+        let green = [0, 255, 0, 255];
+        assert_eq!(
+            Universe::from_image_data(&green, 1, 1, 149)
+                .unwrap()
+                .live_cell_count(),
+            1
+        );
+        assert_eq!(
+            Universe::from_image_data(&green, 1, 1, 150)
+                .unwrap()
+                .live_cell_count(),
+            0
+        );
+    }
+
+    #[test]
+    fn scales_to_nearest_power_of_two() {
+        // A 12x3 image becomes 16x4, with the square stretched to match.
+        let data = white_square(12, 3, 0, 0, 3);
+        let universe = Universe::from_image_data(&data, 12, 3, 128).unwrap();
+        assert_eq!((universe.get_width(), universe.get_height()), (16, 4));
+        assert_eq!(universe.bounding_box(), Some((0, 0, 4, 4)));
+    }
+
+    #[test]
+    fn rejects_mismatched_data() {
+        assert!(Universe::from_image_data(&[0; 12], 2, 2, 128).is_err());
+    }
+
+    #[test]
+    fn reads_life106_bounding_box() {
+        let universe = Universe::from_life106("#Life 1.06\n-1 5\n1 6\n").unwrap();
+        assert_eq!((universe.width, universe.height), (3, 2));
+        assert_eq!(universe.live_coords(), vec![0, 0, 2, 1]);
+    }
+
+    #[test]
+    fn rejects_life106_coordinates_too_far_apart() {
+        let text = format!("#Life 1.06\n{} 0\n{} 0\n", i64::MIN, i64::MAX);
+        assert!(matches!(
+            Universe::from_life106(&text),
+            Err(ParseError::InvalidLife106(_))
+        ));
+    }
+}
@@ -0,0 +1,103 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{ParseError, Universe};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Encode the universe for the fragment of a URL (without the `#`): its
+    /// RLE encoding, percent-encoded; see `Universe::from_url_fragment`.
+    pub fn to_url_fragment(&self) -> String {
+        let mut fragment = String::new();
+        for byte in self.to_rle().bytes() {
+            if byte.is_ascii_alphanumeric() || b"-._~$!".contains(&byte) {
+                fragment.push(byte as char);
+            } else {
+                fragment.push_str(&format!("%{:02X}", byte));
+            }
+        }
+        fragment
+    }
+
+    /// Decode a URL fragment from `to_url_fragment`, with or without its
+    /// leading `#`.
+    pub fn from_url_fragment(fragment: &str) -> Result<Universe, ParseError> {
+        let invalid = |msg: &str| ParseError::InvalidUrlFragment(msg.to_owned());
+        let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+        let mut bytes = Vec::with_capacity(fragment.len());
+        let mut rest = fragment.as_bytes();
+        while let Some((&byte, tail)) = rest.split_first() {
+            if byte == b'%' {
+                let hex = tail
+                    .get(..2)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .ok_or_else(|| invalid("truncated percent escape"))?;
+                let decoded =
+                    u8::from_str_radix(hex, 16).map_err(|_| invalid("bad percent escape"))?;
+                bytes.push(decoded);
+                rest = &tail[2..];
+            } else {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+        let rle = String::from_utf8(bytes).map_err(|_| invalid("not UTF-8"))?;
+        Universe::from_rle(&rle)
+    }
+}
+
+#[cfg(feature = "qr-import")]
+#[wasm_bindgen]
+impl Universe {
+    /// See `Universe::from_qr_png`.
+    #[wasm_bindgen(js_name = from_qr_png)]
+    pub fn from_qr_png_js(data: js_sys::Uint8Array) -> Result<Universe, ParseError> {
+        Universe::from_qr_png(&data.to_vec())
+    }
+}
+
+#[cfg(feature = "qr-import")]
+impl Universe {
+    /// Decode a PNG image of a QR code whose text is a URL fragment, or a URL
+    /// ending in one; see `Universe::from_url_fragment`. Only available with
+    /// the `qr-import` feature.
+    pub fn from_qr_png(data: &[u8]) -> Result<Universe, ParseError> {
+        let invalid = |msg: String| ParseError::InvalidQrCode(msg);
+        let mut decoder = png::Decoder::new(std::io::Cursor::new(data));
+        // Expand palettes and low bit depths, so each sample is a byte.
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder
+            .read_info()
+            .map_err(|err| invalid(err.to_string()))?;
+        let mut pixels = vec![
+            0;
+            reader
+                .output_buffer_size()
+                .ok_or_else(|| invalid("image too large".to_owned()))?
+        ];
+        let info = reader
+            .next_frame(&mut pixels)
+            .map_err(|err| invalid(err.to_string()))?;
+        let samples = info.color_type.samples();
+        let (width, height) = (info.width as usize, info.height as usize);
+        let luma = |x: usize, y: usize| {
+            let pixel = &pixels[y * info.line_size + x * samples..];
+            if samples >= 3 {
+                ((pixel[0] as u32 * 299 + pixel[1] as u32 * 587 + pixel[2] as u32 * 114) / 1000)
+                    as u8
+            } else {
+                pixel[0]
+            }
+        };
+        let mut image = rqrr::PreparedImage::prepare_from_greyscale(width, height, luma);
+        let grid = image
+            .detect_grids()
+            .into_iter()
+            .next()
+            .ok_or_else(|| invalid("no QR code found".to_owned()))?;
+        let (_, text) = grid.decode().map_err(|err| invalid(err.to_string()))?;
+        let fragment = text
+            .rsplit_once('#')
+            .map_or(text.as_str(), |(_, fragment)| fragment);
+        Universe::from_url_fragment(fragment)
+    }
+}
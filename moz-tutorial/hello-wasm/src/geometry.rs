@@ -0,0 +1,247 @@
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{islands, Cell, Universe};
+
+/// The `voronoi_map` label of cells with no live cell to be nearest to.
+const UNREACHED: u32 = u32::MAX;
+
+#[wasm_bindgen]
+impl Universe {
+    /// The convex hull of the live cells' positions, as a flat list of
+    /// `[x0, y0, x1, y1, ...]` vertices in counter-clockwise order (with y
+    /// pointing up; on a canvas, where y points down, it runs clockwise).
+    /// Empty if there are fewer than 3 live cells.
+    pub fn convex_hull(&self) -> Vec<u32> {
+        let mut points: Vec<(i64, i64)> = self
+            .cells()
+            .filter(|(_, cell)| cell.is_live())
+            .map(|(coord, _)| (coord.x as i64, coord.y as i64))
+            .collect();
+        if points.len() < 3 {
+            return Vec::new();
+        }
+        // Andrew's monotone chain: Graham scan over points sorted by x, once
+        // for the lower half of the hull and once for the upper half.
+        points.sort_unstable();
+        let mut hull: Vec<(i64, i64)> = Vec::with_capacity(2 * points.len());
+        for pass in 0..2 {
+            let start = hull.len();
+            for &point in points.iter() {
+                while hull.len() >= start + 2
+                    && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0
+                {
+                    hull.pop();
+                }
+                hull.push(point);
+            }
+            // The last point is the first point of the other half.
+            hull.pop();
+            if pass == 0 {
+                points.reverse();
+            }
+        }
+        hull.iter()
+            .flat_map(|&(x, y)| vec![x as u32, y as u32])
+            .collect()
+    }
+
+    /// Render the universe with its convex hull outlined in `color`.
+    pub fn render2d_with_hull(
+        &self,
+        canvas: &CanvasRenderingContext2d,
+        color: &str,
+    ) -> Result<(), JsValue> {
+        self.put_pixels(canvas, &self.pixels())?;
+        let hull = self.convex_hull();
+        if hull.is_empty() {
+            return Ok(());
+        }
+        canvas.begin_path();
+        for (i, vertex) in hull.chunks_exact(2).enumerate() {
+            // Through the center of each cell.
+            let (x, y) = (vertex[0] as f64 + 0.5, vertex[1] as f64 + 0.5);
+            if i == 0 {
+                canvas.move_to(x, y);
+            } else {
+                canvas.line_to(x, y);
+            }
+        }
+        canvas.close_path();
+        canvas.set_stroke_style_str(color);
+        canvas.stroke();
+        Ok(())
+    }
+
+    /// Estimate the fractal dimension of the live cells by box counting: the
+    /// negated slope of log(occupied boxes) against log(box size), for box sizes
+    /// 1, 2, 4, ... up to a quarter of the smaller dimension. A filled
+    /// rectangle gives about 2. NaN if there are fewer than 20 live cells, or
+    /// the universe is too small for two box sizes.
+    pub fn box_counting_dimension(&self) -> f64 {
+        if self.live_count < 20 {
+            return f64::NAN;
+        }
+        let max_size = self.width.min(self.height) / 4;
+        let samples: Vec<(f64, f64)> = std::iter::successors(Some(1), |size| Some(size * 2))
+            .take_while(|&size| size <= max_size)
+            .map(|size| {
+                let (columns, rows) = (self.width.div_ceil(size), self.height.div_ceil(size));
+                let mut occupied = vec![false; columns * rows];
+                for (coord, cell) in self {
+                    if cell.is_live() {
+                        occupied[(coord.y / size) * columns + coord.x / size] = true;
+                    }
+                }
+                let count = occupied.iter().filter(|&&box_| box_).count();
+                ((size as f64).ln(), (count as f64).ln())
+            })
+            .collect();
+        if samples.len() < 2 {
+            return f64::NAN;
+        }
+        let n = samples.len() as f64;
+        let mean_x = samples.iter().map(|s| s.0).sum::<f64>() / n;
+        let mean_y = samples.iter().map(|s| s.1).sum::<f64>() / n;
+        let covariance: f64 = samples
+            .iter()
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum();
+        let variance: f64 = samples.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+        -covariance / variance
+    }
+
+    /// The mean position of the live cells, as `[x, y]`; empty if none are live.
+    /// Positions are taken as they are, so a pattern crossing a wrapped edge
+    /// has its centroid somewhere in between.
+    #[wasm_bindgen(js_name = centroid)]
+    pub fn centroid_js(&self) -> Vec<f64> {
+        self.centroid().map_or(Vec::new(), |(x, y)| vec![x, y])
+    }
+
+    /// How far the centroid of the live cells gets from where it started over
+    /// the next `generations` ticks, without changing this universe: 0 for a
+    /// still life or a symmetric oscillator, the distance traveled for a
+    /// spaceship. Generations with no live cells don't count.
+    pub fn measure_stability_radius(&self, generations: u32) -> f64 {
+        let (x0, y0) = match self.centroid() {
+            Some(start) => start,
+            None => return 0.0,
+        };
+        let mut future = self.scratch_copy();
+        let mut radius: f64 = 0.0;
+        for _ in 0..generations {
+            future.step();
+            if let Some((x, y)) = future.centroid() {
+                radius = radius.max((x - x0).hypot(y - y0));
+            }
+        }
+        radius
+    }
+
+    /// Label each cell with the index (`y * width + x`) of its nearest live
+    /// cell, by Chebyshev distance under the current topology; live cells are
+    /// their own label. Ties are broken deterministically, but not by any
+    /// particular rule. Cells with no live cell in reach (as in an empty
+    /// universe) are labeled `u32::MAX`.
+    pub fn voronoi_map(&self) -> Vec<u32> {
+        let mut labels = vec![UNREACHED; self.content.len()];
+        let mut frontier = std::collections::VecDeque::new();
+        for (here, cell) in self {
+            if cell.is_live() {
+                let index = here.y * self.width + here.x;
+                labels[index] = index as u32;
+                frontier.push_back(here);
+            }
+        }
+        // Breadth-first from every live cell at once: each ring of the search is
+        // one step further in Chebyshev distance.
+        while let Some(here) = frontier.pop_front() {
+            let label = labels[here.y * self.width + here.x];
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let (x, y) = (here.x as isize + dx, here.y as isize + dy);
+                    if let Some(next) = self.topology.resolve(x, y, self.width, self.height) {
+                        let index = next.y * self.width + next.x;
+                        if labels[index] == UNREACHED {
+                            labels[index] = label;
+                            frontier.push_back(next);
+                        }
+                    }
+                }
+            }
+        }
+        labels
+    }
+
+    /// Render the universe with each cell colored by its `voronoi_map` label,
+    /// using the same colors as `render2d_island_colors`.
+    pub fn render2d_voronoi(&self, canvas: &CanvasRenderingContext2d) -> Result<(), JsValue> {
+        let labels = self.voronoi_map();
+        let pixels = self.pixels_with(|i, _| match labels[i] {
+            UNREACHED => Cell::Dead as u32,
+            label => islands::label_color(label as usize),
+        });
+        self.put_pixels(canvas, &pixels)
+    }
+}
+
+impl Universe {
+    /// The mean position of the live cells, if any are live; see `centroid_js`.
+    pub fn centroid(&self) -> Option<(f64, f64)> {
+        if self.live_count == 0 {
+            return None;
+        }
+        let (mut x, mut y) = (0, 0);
+        for (here, cell) in self {
+            if cell.is_live() {
+                x += here.x;
+                y += here.y;
+            }
+        }
+        let n = self.live_count as f64;
+        Some((x as f64 / n, y as f64 / n))
+    }
+}
+
+/// The z component of (a - o) x (b - o): positive if o, a, b turn counter-clockwise.
+fn cross(o: (i64, i64), a: (i64, i64), b: (i64, i64)) -> i64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filled_square_is_two_dimensional() {
+        let mut universe = Universe::new(64, 64);
+        universe.randomize_with_density(0, 1.0);
+        let dimension = universe.box_counting_dimension();
+        assert!((1.9..=2.0).contains(&dimension), "{}", dimension);
+    }
+
+    #[test]
+    fn sierpinski_triangle_dimension() {
+        // Pascal's triangle mod 2, whose dimension is log 3 / log 2 = 1.585.
+        // A checkerboard is no fractal: every box bigger than a cell is occupied.
+        let mut universe = Universe::new(64, 64);
+        let live: Vec<u32> = (0..64u32)
+            .flat_map(|y| {
+                (0..=y)
+                    .filter(move |x| x & y == *x)
+                    .flat_map(move |x| [x, y])
+            })
+            .collect();
+        universe.set_cells_bulk(live, true);
+        let dimension = universe.box_counting_dimension();
+        assert!((1.5..=1.6).contains(&dimension), "{}", dimension);
+    }
+
+    #[test]
+    fn box_counting_needs_twenty_live_cells() {
+        let mut universe = Universe::new(64, 64);
+        universe.set_cells_bulk((0..19).flat_map(|x| [x, 0]).collect(), true);
+        assert!(universe.box_counting_dimension().is_nan());
+    }
+}
@@ -0,0 +1,80 @@
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::Universe;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Start or stop counting, for each cell, the ticks after which it is live.
+    pub fn set_track_heatmap(&mut self, enabled: bool) {
+        self.visits = if enabled {
+            Some(vec![0; self.content.len()])
+        } else {
+            None
+        };
+    }
+
+    /// The number of ticks after which the cell at (x, y) was live, since heat
+    /// map tracking was enabled; always 0 unless it is.
+    pub fn get_visit_count(&self, x: usize, y: usize) -> u32 {
+        let (x, y) = (x % self.width, y % self.height);
+        self.visits
+            .as_ref()
+            .map_or(0, |visits| visits[y * self.width + x])
+    }
+
+    /// Render the visit counts from black (never live) through red and yellow
+    /// to white (the most visited), on a linear scale.
+    pub fn render2d_heatmap(&self, canvas: &CanvasRenderingContext2d) -> Result<(), JsValue> {
+        self.put_pixels(canvas, &self.compute_heatmap_colors(false))
+    }
+
+    /// Render the visit counts like `render2d_heatmap`, but scaled by
+    /// `log(1 + count) / log(1 + max_count)`, so that rarely visited cells are
+    /// still visible next to very busy ones.
+    pub fn render2d_heatmap_log(&self, canvas: &CanvasRenderingContext2d) -> Result<(), JsValue> {
+        self.put_pixels(canvas, &self.compute_heatmap_colors(true))
+    }
+}
+
+impl Universe {
+    /// Count a visit for each live cell, if heat map tracking is enabled.
+    pub(crate) fn count_visits(&mut self) {
+        if let Some(visits) = &mut self.visits {
+            for (count, cell) in visits.iter_mut().zip(self.content.iter()) {
+                if cell.is_live() {
+                    *count += 1;
+                }
+            }
+        }
+    }
+
+    /// RGBA pixel data for the heat map, on a linear or logarithmic scale.
+    pub(crate) fn compute_heatmap_colors(&self, log_scale: bool) -> Vec<u8> {
+        let visits = match &self.visits {
+            Some(visits) => visits,
+            None => return self.pixels_with(|_, _| heat_color(0.0)),
+        };
+        let scale = |count: u32| {
+            if log_scale {
+                (count as f64).ln_1p()
+            } else {
+                count as f64
+            }
+        };
+        let max = scale(visits.iter().copied().max().unwrap_or(0));
+        self.pixels_with(|i, _| {
+            if max > 0.0 {
+                heat_color(scale(visits[i]) / max)
+            } else {
+                heat_color(0.0)
+            }
+        })
+    }
+}
+
+/// The `0xRRGGBBAA` color for `heat` in [0, 1]: black, then red, yellow, and white.
+fn heat_color(heat: f64) -> u32 {
+    let channel = |offset: f64| ((heat * 3.0 - offset).clamp(0.0, 1.0) * 255.0).round() as u32;
+    channel(0.0) << 24 | channel(1.0) << 16 | channel(2.0) << 8 | 0xFF
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::rule::parse_birth_survival;
+use crate::{Cell, UniverseError};
+
+/// Axial offsets of the six neighbors of a hex cell.
+const NEIGHBOR_OFFSETS: [(i32, i32); 6] = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+/// A hexagon-shaped universe of hexagonal cells, addressed by axial coordinates (q, r).
+/// Cells beyond `radius` of the center do not exist, and count as dead.
+#[wasm_bindgen]
+pub struct HexUniverse {
+    radius: usize,
+    cells: HashMap<(i32, i32), bool>,
+}
+
+impl HexUniverse {
+    /// The six neighbors of the cell at (q, r).
+    pub fn neighbors(q: i32, r: i32) -> [(i32, i32); 6] {
+        NEIGHBOR_OFFSETS.map(|(dq, dr)| (q + dq, r + dr))
+    }
+}
+
+#[wasm_bindgen]
+impl HexUniverse {
+    /// Create an all-dead universe of the given radius.
+    pub fn new(radius: usize) -> Self {
+        let n = radius as i32;
+        let cells = (-n..=n)
+            .flat_map(|q| ((-n).max(-q - n)..=n.min(-q + n)).map(move |r| ((q, r), false)))
+            .collect();
+        HexUniverse { radius, cells }
+    }
+
+    pub fn get_radius(&self) -> usize {
+        self.radius
+    }
+
+    /// Whether the cell at (q, r) is live; false outside the universe.
+    pub fn get_cell(&self, q: i32, r: i32) -> bool {
+        self.cells.get(&(q, r)).copied().unwrap_or(false)
+    }
+
+    /// Set the cell at (q, r). Cells outside the universe are ignored.
+    pub fn set_cell(&mut self, q: i32, r: i32, alive: bool) {
+        if let Some(cell) = self.cells.get_mut(&(q, r)) {
+            *cell = alive;
+        }
+    }
+
+    /// Advance one tick under an outer totalistic rule in B/S notation, e.g. "B2/S34".
+    /// Neighbor counts range from 0 to 6.
+    pub fn tick(&mut self, rule: &str) -> Result<(), UniverseError> {
+        let (birth, survival) = parse_birth_survival(rule, 6)?;
+        self.cells = self
+            .cells
+            .iter()
+            .map(|(&(q, r), &alive)| {
+                let count = HexUniverse::neighbors(q, r)
+                    .iter()
+                    .filter(|(q, r)| self.get_cell(*q, *r))
+                    .count() as u8;
+                let next = if alive {
+                    survival.contains(&count)
+                } else {
+                    birth.contains(&count)
+                };
+                ((q, r), next)
+            })
+            .collect();
+        Ok(())
+    }
+
+    /// Draw the universe as flat-top hexagons with the given circumradius, in pixels.
+    pub fn render2d(
+        &self,
+        canvas: &CanvasRenderingContext2d,
+        cell_size: f64,
+    ) -> Result<(), JsValue> {
+        let sqrt3 = 3f64.sqrt();
+        let radius = self.radius as f64;
+        let origin_x = cell_size * (1.5 * radius + 1.0);
+        let origin_y = cell_size * sqrt3 * (radius + 0.5);
+
+        for (&(q, r), &alive) in self.cells.iter() {
+            let center_x = origin_x + cell_size * 1.5 * q as f64;
+            let center_y = origin_y + cell_size * sqrt3 * (r as f64 + q as f64 / 2.0);
+            let cell = if alive { Cell::Alive } else { Cell::Dead };
+
+            canvas.begin_path();
+            for corner in 0..6 {
+                let angle = std::f64::consts::FRAC_PI_3 * corner as f64;
+                let x = center_x + cell_size * angle.cos();
+                let y = center_y + cell_size * angle.sin();
+                if corner == 0 {
+                    canvas.move_to(x, y);
+                } else {
+                    canvas.line_to(x, y);
+                }
+            }
+            canvas.close_path();
+            canvas.set_fill_style_str(&cell.css_color());
+            canvas.fill();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The live cells of `universe`, sorted.
+    fn live(universe: &HexUniverse) -> Vec<(i32, i32)> {
+        let mut cells: Vec<(i32, i32)> = universe
+            .cells
+            .iter()
+            .filter(|(_, &alive)| alive)
+            .map(|(&cell, _)| cell)
+            .collect();
+        cells.sort();
+        cells
+    }
+
+    #[test]
+    fn hexagon_has_centered_hexagonal_number_of_cells() {
+        for radius in 0..5 {
+            assert_eq!(
+                HexUniverse::new(radius).cells.len(),
+                3 * radius * radius + 3 * radius + 1
+            );
+        }
+    }
+
+    #[test]
+    fn neighbors_are_mutual() {
+        let neighbors = HexUniverse::neighbors(2, -1);
+        assert_eq!(neighbors.len(), 6);
+        for (q, r) in neighbors {
+            assert!(HexUniverse::neighbors(q, r).contains(&(2, -1)));
+        }
+    }
+
+    #[test]
+    fn cells_outside_radius_are_dead() {
+        let mut universe = HexUniverse::new(2);
+        universe.set_cell(2, 1, true);
+        assert!(!universe.get_cell(2, 1));
+        universe.set_cell(2, -1, true);
+        assert!(universe.get_cell(2, -1));
+    }
+
+    #[test]
+    fn b2_s34_pair_oscillates_with_domino() {
+        // Two cells sharing two neighbors give birth to those neighbors and
+        // die; the resulting domino does the same in reverse.
+        let mut universe = HexUniverse::new(4);
+        universe.set_cell(0, 1, true);
+        universe.set_cell(1, -1, true);
+        universe.tick("B2/S34").unwrap();
+        assert_eq!(live(&universe), [(0, 0), (1, 0)]);
+        universe.tick("B2/S34").unwrap();
+        assert_eq!(live(&universe), [(0, 1), (1, -1)]);
+    }
+
+    #[test]
+    fn tick_rejects_counts_above_six() {
+        let mut universe = HexUniverse::new(1);
+        assert!(universe.tick("B7/S34").is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Op, Universe};
+
+#[wasm_bindgen]
+impl Universe {
+    /// Keep up to `capacity` previous generations for `undo`; 0 disables undo.
+    pub fn set_undo_capacity(&mut self, capacity: usize) {
+        self.record(Op::SetUndoCapacity(capacity));
+        self.undo_capacity = capacity;
+        while self.history.len() > capacity {
+            self.history.pop_front();
+        }
+    }
+
+    /// Return to the generation before the most recent tick.
+    /// Returns false if no earlier generation is available.
+    /// Cell ages restart from zero.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop_back() {
+            Some(content) => {
+                self.record(Op::Undo);
+                self.prev_content = None;
+                self.content = content;
+                self.recount_live();
+                self.generation -= 1;
+                self.population_history.pop_back();
+                if let Some(ages) = &mut self.ages {
+                    ages.iter_mut().for_each(|age| *age = 0);
+                }
+                self.debug_validate();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Start saving the previous generation on each tick, for `step_back`.
+    pub fn enable_step_back(&mut self) {
+        self.record(Op::EnableStepBack);
+        self.step_back_enabled = true;
+    }
+
+    /// Stop saving the previous generation, and discard any saved one.
+    pub fn disable_step_back(&mut self) {
+        self.record(Op::DisableStepBack);
+        self.step_back_enabled = false;
+        self.prev_content = None;
+    }
+
+    /// Return to the generation before the most recent tick, if step-back is
+    /// enabled. Only one step is kept; returns false if it isn't available.
+    /// Cell ages restart from zero.
+    pub fn step_back(&mut self) -> bool {
+        match self.prev_content.take() {
+            Some(content) => {
+                self.record(Op::StepBack);
+                // The same generation is also the newest undo entry.
+                self.history.pop_back();
+                self.content = content;
+                self.recount_live();
+                self.generation -= 1;
+                self.population_history.pop_back();
+                if let Some(ages) = &mut self.ages {
+                    ages.iter_mut().for_each(|age| *age = 0);
+                }
+                self.debug_validate();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Universe {
+    /// Save `content`, the generation just replaced by a tick, for `undo`
+    /// and `step_back`.
+    pub(crate) fn push_history(&mut self, content: Vec<Cell>) {
+        if self.undo_capacity == 0 {
+            if self.step_back_enabled {
+                self.prev_content = Some(content);
+            }
+            return;
+        }
+        if self.step_back_enabled {
+            self.prev_content = Some(content.clone());
+        }
+        if self.history.len() == self.undo_capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_back_undoes_tick() {
+        for seed in 0..8 {
+            let mut universe = Universe::new(12, 10);
+            universe.randomize(seed);
+            universe.tick();
+            universe.enable_step_back();
+            let before = universe.content.clone();
+            let population = universe.live_cell_count();
+            universe.tick();
+            assert!(universe.step_back());
+            assert_eq!(universe.content, before);
+            assert_eq!(universe.live_cell_count(), population);
+            assert_eq!(universe.get_generation(), 1);
+        }
+    }
+
+    #[test]
+    fn step_back_keeps_one_step() {
+        let mut universe = Universe::new(8, 8);
+        universe.randomize(1);
+        assert!(!universe.step_back());
+        universe.enable_step_back();
+        universe.tick_n(2);
+        assert!(universe.step_back());
+        assert!(!universe.step_back());
+        // Ticking again re-advances, and saves a new step.
+        universe.tick();
+        assert_eq!(universe.get_generation(), 2);
+        assert!(universe.step_back());
+    }
+
+    #[test]
+    fn disable_step_back_discards_saved_step() {
+        let mut universe = Universe::new(8, 8);
+        universe.enable_step_back();
+        universe.tick();
+        universe.disable_step_back();
+        assert!(!universe.step_back());
+        universe.tick();
+        assert!(!universe.step_back());
+    }
+}
@@ -0,0 +1,423 @@
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{Cell, Coord, LogLevel, Topology, Universe, UniverseError};
+
+/// Multiplier spreading consecutive labels across the color space: 2^32 / phi.
+const GOLDEN_RATIO_HASH: u64 = 0x9E37_79B9;
+
+/// A distinct, opaque `0xRRGGBBAA` color for the island (or other region) `label`.
+pub(crate) fn label_color(label: usize) -> u32 {
+    let rgb = (label as u64 + 1).wrapping_mul(GOLDEN_RATIO_HASH) % 0xFF_FF_FF;
+    (rgb as u32) << 8 | 0xFF
+}
+
+/// Whether cells with `connectivity` 4 or 8 neighbors include the diagonals.
+pub(crate) fn diagonals(connectivity: u8) -> Result<bool, UniverseError> {
+    match connectivity {
+        4 => Ok(false),
+        8 => Ok(true),
+        _ => Err(UniverseError::InvalidConnectivity(connectivity)),
+    }
+}
+
+/// An island that repeats itself; see `Universe::detect_oscillators`.
+#[wasm_bindgen]
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct OscillatorInfo {
+    period: u32,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+#[wasm_bindgen]
+impl OscillatorInfo {
+    pub fn period(&self) -> u32 {
+        self.period
+    }
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+/// A group of cells that repeats itself displaced; see `Universe::detect_spaceships`.
+#[wasm_bindgen]
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct SpaceshipInfo {
+    period: u32,
+    dx: i32,
+    dy: i32,
+    bbox_at_start: [u32; 4],
+}
+
+#[wasm_bindgen]
+impl SpaceshipInfo {
+    pub fn period(&self) -> u32 {
+        self.period
+    }
+    /// How far the spaceship moves right each period.
+    pub fn dx(&self) -> i32 {
+        self.dx
+    }
+    /// How far the spaceship moves down each period.
+    pub fn dy(&self) -> i32 {
+        self.dy
+    }
+    /// The spaceship's current bounding box, as `[x, y, width, height]`.
+    pub fn bbox_at_start(&self) -> Vec<u32> {
+        self.bbox_at_start.to_vec()
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// See `Universe::detect_still_lifes`. Returns `[x, y, w, h]` for each
+    /// still life, flattened.
+    #[wasm_bindgen(js_name = detect_still_lifes)]
+    pub fn detect_still_lifes_js(&self) -> js_sys::Uint32Array {
+        let boxes: Vec<u32> = self.detect_still_lifes().concat();
+        js_sys::Uint32Array::from(&boxes[..])
+    }
+
+    /// The groups of cells that, on their own, return to their current state
+    /// within `max_period` ticks, with a period greater than 1. Cells are grouped
+    /// when they are close enough to interact, so an oscillator made of several
+    /// islands (like the pulsar) is found as one.
+    pub fn detect_oscillators(&self, max_period: u32) -> Vec<OscillatorInfo> {
+        self.clusters(2, true)
+            .iter()
+            .filter_map(|island| {
+                let period = self.period_of(island, max_period)?;
+                if period == 1 {
+                    return None;
+                }
+                let [x, y, width, height] = bounds(island);
+                Some(OscillatorInfo {
+                    period,
+                    x,
+                    y,
+                    width,
+                    height,
+                })
+            })
+            .collect()
+    }
+
+    /// The groups of cells that, on their own, take on their current shape at a
+    /// different position within `max_ticks` ticks. Cells are grouped as in
+    /// `detect_oscillators`.
+    pub fn detect_spaceships(&self, max_ticks: u32) -> Vec<SpaceshipInfo> {
+        self.clusters(2, true)
+            .iter()
+            .filter_map(|group| {
+                let mut alone = self.isolate(group, max_ticks as usize + 1);
+                let (x0, y0, initial) = live_shape(&alone)?;
+                (1..=max_ticks).find_map(|period| {
+                    alone.step();
+                    let (x, y, shape) = live_shape(&alone)?;
+                    if shape != initial || (x, y) == (x0, y0) {
+                        return None;
+                    }
+                    Some(SpaceshipInfo {
+                        period,
+                        dx: x as i32 - x0 as i32,
+                        dy: y as i32 - y0 as i32,
+                        bbox_at_start: bounds(group),
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Render the universe with each island, under 4- or 8-`connectivity`,
+    /// in its own color. Dead cells are drawn in the dead color.
+    pub fn render2d_island_colors(
+        &self,
+        canvas: &CanvasRenderingContext2d,
+        connectivity: u8,
+    ) -> Result<(), JsValue> {
+        let mut labels = vec![0; self.content.len()];
+        for (label, island) in self
+            .clusters(1, diagonals(connectivity)?)
+            .iter()
+            .enumerate()
+        {
+            for here in island {
+                labels[here.y * self.width + here.x] = label;
+            }
+        }
+        let pixels = self.pixels_with(|i, cell| {
+            if cell.is_live() {
+                label_color(labels[i])
+            } else {
+                Cell::Dead as u32
+            }
+        });
+        self.put_pixels(canvas, &pixels)
+    }
+}
+
+impl Universe {
+    /// Groups of live cells connected through their neighborhoods, under the
+    /// current topology. Islands are ordered by their first cell, in row-major order.
+    pub fn islands(&self) -> Vec<Vec<Coord>> {
+        self.clusters(1, true)
+    }
+
+    /// Groups of live cells linked by chains of live cells at most `reach`
+    /// apart in each axis, ordered by their first cell in row-major order.
+    /// Without `diagonals`, the steps must be at most `reach` in total.
+    /// With a reach of 2, cells in different groups share no neighbors,
+    /// so the groups evolve independently (until they grow into each other).
+    pub(crate) fn clusters(&self, reach: usize, diagonals: bool) -> Vec<Vec<Coord>> {
+        let reach = reach as isize;
+        let mut visited = vec![false; self.content.len()];
+        let mut islands = Vec::new();
+        for (start, cell) in self {
+            let index = start.y * self.width + start.x;
+            if !cell.is_live() || visited[index] {
+                continue;
+            }
+            visited[index] = true;
+            let mut island = Vec::new();
+            let mut pending = vec![start];
+            while let Some(here) = pending.pop() {
+                island.push(here);
+                for dy in -reach..=reach {
+                    for dx in -reach..=reach {
+                        if !diagonals && dx.abs() + dy.abs() > reach {
+                            continue;
+                        }
+                        let (x, y) = (here.x as isize + dx, here.y as isize + dy);
+                        let next = match self.topology.resolve(x, y, self.width, self.height) {
+                            Some(next) => next,
+                            None => continue,
+                        };
+                        let index = next.y * self.width + next.x;
+                        if self[next].is_live() && !visited[index] {
+                            visited[index] = true;
+                            pending.push(next);
+                        }
+                    }
+                }
+            }
+            islands.push(island);
+        }
+        islands
+    }
+
+    /// The bounding boxes `[x, y, width, height]` of the islands that are
+    /// still lifes on their own: unchanged by a tick with nothing else around.
+    pub fn detect_still_lifes(&self) -> Vec<[u32; 4]> {
+        self.islands()
+            .iter()
+            .filter(|island| self.period_of(island, 1) == Some(1))
+            .map(|island| bounds(island))
+            .collect()
+    }
+
+    /// The number of ticks, up to `max_period`, after which `island` first
+    /// returns to its current state when alone in the universe.
+    fn period_of(&self, island: &[Coord], max_period: u32) -> Option<u32> {
+        // A pattern grows by at most one cell per tick in each direction.
+        let mut alone = self.isolate(island, max_period as usize + 1);
+        let initial: Vec<bool> = alone.content.iter().map(|cell| cell.is_live()).collect();
+        (1..=max_period).find(|_| {
+            alone.step();
+            alone
+                .content
+                .iter()
+                .map(|cell| cell.is_live())
+                .eq(initial.iter().copied())
+        })
+    }
+
+    /// A universe containing only `island`. Islands at least `margin` cells
+    /// from the edges are copied into a small bounded universe with a dead
+    /// margin that wide; others keep the full universe so they can still wrap.
+    fn isolate(&self, island: &[Coord], margin: usize) -> Universe {
+        let (min_x, min_y, max_x, max_y) = extent(island);
+        let interior = min_x >= margin
+            && min_y >= margin
+            && max_x + margin < self.width
+            && max_y + margin < self.height;
+        let mut alone = if interior {
+            let mut small = self.blank_like(
+                max_x - min_x + 1 + 2 * margin,
+                max_y - min_y + 1 + 2 * margin,
+            );
+            small.topology = Topology::Bounded;
+            small
+        } else {
+            self.blank_like(self.width, self.height)
+        };
+        alone.log_level = LogLevel::None;
+        for coord in island {
+            let here = if interior {
+                Coord {
+                    y: coord.y - min_y + margin,
+                    x: coord.x - min_x + margin,
+                }
+            } else {
+                *coord
+            };
+            alone.put(here, self[*coord]);
+        }
+        alone
+    }
+}
+
+/// The width, height, and live cells of a bounding box.
+type Shape = (usize, usize, Vec<bool>);
+
+/// The top-left corner of the live cells' bounding box, and their shape
+/// within it; None if there are no live cells.
+fn live_shape(universe: &Universe) -> Option<(usize, usize, Shape)> {
+    let (x, y, width, height) = universe.bounding_box()?;
+    let cells = universe.subgrid(x, y, width, height);
+    let live = cells.content.iter().map(|cell| cell.is_live()).collect();
+    Some((x, y, (width, height, live)))
+}
+
+/// The bounding box `[x, y, width, height]` of `island`.
+fn bounds(island: &[Coord]) -> [u32; 4] {
+    let (min_x, min_y, max_x, max_y) = extent(island);
+    [
+        min_x as u32,
+        min_y as u32,
+        (max_x - min_x + 1) as u32,
+        (max_y - min_y + 1) as u32,
+    ]
+}
+
+/// The smallest and largest coordinates in `island`: (min_x, min_y, max_x, max_y).
+fn extent(island: &[Coord]) -> (usize, usize, usize, usize) {
+    island.iter().fold(
+        (usize::MAX, usize::MAX, 0, 0),
+        |(min_x, min_y, max_x, max_y), c| {
+            (
+                min_x.min(c.x),
+                min_y.min(c.y),
+                max_x.max(c.x),
+                max_y.max(c.y),
+            )
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PULSAR: [&str; 13] = [
+        "..OOO...OOO..",
+        ".............",
+        "O....O.O....O",
+        "O....O.O....O",
+        "O....O.O....O",
+        "..OOO...OOO..",
+        ".............",
+        "..OOO...OOO..",
+        "O....O.O....O",
+        "O....O.O....O",
+        "O....O.O....O",
+        ".............",
+        "..OOO...OOO..",
+    ];
+
+    /// A `width` x `height` universe with each pattern drawn with its top-left
+    /// at the given position.
+    fn universe_with(
+        width: usize,
+        height: usize,
+        patterns: &[(usize, usize, &[&str])],
+    ) -> Universe {
+        let mut universe = Universe::new(width, height);
+        for &(x, y, pattern) in patterns {
+            Universe::from_pattern(pattern)
+                .unwrap()
+                .tile_into(&mut universe, x, y, 1, 1);
+        }
+        universe
+    }
+
+    #[test]
+    fn detects_blinker_and_pulsar() {
+        let universe = universe_with(
+            50,
+            30,
+            &[
+                (5, 5, &["OOO"]),
+                (25, 8, &PULSAR),
+                // A block is a still life, so it is not reported.
+                (5, 20, &["OO", "OO"]),
+            ],
+        );
+        assert_eq!(
+            universe.detect_oscillators(5),
+            [
+                OscillatorInfo {
+                    period: 2,
+                    x: 5,
+                    y: 5,
+                    width: 3,
+                    height: 1,
+                },
+                OscillatorInfo {
+                    period: 3,
+                    x: 25,
+                    y: 8,
+                    width: 13,
+                    height: 13,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn oscillator_beyond_max_period_is_not_detected() {
+        let universe = universe_with(30, 30, &[(8, 8, &PULSAR)]);
+        assert!(universe.detect_oscillators(2).is_empty());
+    }
+
+    #[test]
+    fn detects_still_lifes() {
+        let universe = universe_with(20, 20, &[(2, 2, &["OO", "OO"]), (10, 10, &["OOO"])]);
+        assert_eq!(universe.detect_still_lifes(), [[2, 2, 2, 2]]);
+    }
+
+    #[test]
+    fn detects_glider() {
+        let universe = universe_with(
+            30,
+            30,
+            &[(13, 13, &[".O.", "..O", "OOO"]), (3, 3, &["OOO"])],
+        );
+        assert_eq!(
+            universe.detect_spaceships(8),
+            [SpaceshipInfo {
+                period: 4,
+                dx: 1,
+                dy: 1,
+                bbox_at_start: [13, 13, 3, 3],
+            }]
+        );
+    }
+
+    #[test]
+    fn spaceship_slower_than_max_ticks_is_not_detected() {
+        let universe = universe_with(30, 30, &[(13, 13, &[".O.", "..O", "OOO"])]);
+        assert!(universe.detect_spaceships(3).is_empty());
+    }
+}
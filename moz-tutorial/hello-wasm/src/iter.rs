@@ -0,0 +1,63 @@
+use crate::{Cell, Coord, Universe};
+
+/// Iterator over the cells of a Universe, in row-major order.
+pub struct Cells<'a> {
+    width: usize,
+    inner: std::iter::Enumerate<std::slice::Iter<'a, Cell>>,
+}
+
+impl<'a> Iterator for Cells<'a> {
+    type Item = (Coord, Cell);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (i, cell) = self.inner.next()?;
+        Some((Coord::from_index(i, self.width), *cell))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Mutable iterator over the cells of a Universe, in row-major order.
+pub struct CellsMut<'a> {
+    width: usize,
+    inner: std::iter::Enumerate<std::slice::IterMut<'a, Cell>>,
+}
+
+impl<'a> Iterator for CellsMut<'a> {
+    type Item = (Coord, &'a mut Cell);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (i, cell) = self.inner.next()?;
+        Some((Coord::from_index(i, self.width), cell))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a> IntoIterator for &'a Universe {
+    type Item = (Coord, Cell);
+    type IntoIter = Cells<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Cells {
+            width: self.width,
+            inner: self.content.iter().enumerate(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Universe {
+    type Item = (Coord, &'a mut Cell);
+    type IntoIter = CellsMut<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        CellsMut {
+            width: self.width,
+            inner: self.content.iter_mut().enumerate(),
+        }
+    }
+}
@@ -1,18 +1,125 @@
+use std::collections::VecDeque;
 use std::ops::{Index, IndexMut};
+use std::sync::{Arc, Mutex};
 
-use rand::{RngCore, SeedableRng};
+use rand::{Rng, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 use web_sys::{CanvasRenderingContext2d, ImageData};
 
-/// Cell, represented by its color-state (u32 RGBA)
-/// This lets us treat a
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Default, Copy, Clone)]
+mod ant;
+mod async_init;
+mod builder;
+mod canonical;
+mod draw;
+mod error;
+mod fft;
+mod formats;
+mod fragment;
+mod geometry;
+mod heatmap;
+mod hex;
+mod history;
+mod islands;
+mod iter;
+mod library;
+mod log;
+mod noise;
+mod predecessors;
+mod reaction;
+mod render;
+mod replay;
+mod rle;
+mod rule;
+mod run;
+mod selection;
+mod shared;
+#[cfg(feature = "png-export")]
+mod spritesheet;
+mod stats;
+mod stencil;
+mod topology;
+mod trail;
+mod transform;
+mod validate;
+mod wolfram;
+
+pub use ant::LangtonsAnt;
+pub use builder::UniverseBuilder;
+pub use error::{ParseError, UniverseError};
+pub use hex::HexUniverse;
+pub use islands::{OscillatorInfo, SpaceshipInfo};
+pub use iter::{Cells, CellsMut};
+pub use library::{PatternEntry, PatternLibrary};
+pub use log::LogLevel;
+pub use render::RenderConfig;
+pub use replay::{Op, ReplayLog};
+pub use rule::Rule;
+pub use run::{PeriodResult, RunResult, StopCondition};
+pub use stats::{ClusterStats, TickStats};
+use topology::Topology;
+pub use transform::SuperimposeMode;
+pub use wolfram::WolframUniverse;
+
+/// The largest number of cells a Universe may hold (4096 x 4096).
+pub const MAX_CELLS: usize = 4096 * 4096;
+
+/// The number of cells in a `width` x `height` universe, if it has at least
+/// one row and column and is within `MAX_CELLS`.
+fn checked_cells(width: usize, height: usize) -> Result<usize, UniverseError> {
+    if width == 0 || height == 0 {
+        return Err(UniverseError::EmptyDimension { width, height });
+    }
+    width
+        .checked_mul(height)
+        .filter(|cells| *cells <= MAX_CELLS)
+        .ok_or(UniverseError::DimensionTooLarge { width, height })
+}
+
+/// Cell, represented by its color-state (u32 RGBA, red in the high byte)
+/// This lets us treat a cell's discriminant as its pixel value.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Default, Copy, Clone, Serialize, Deserialize)]
 #[repr(u32)]
-enum Cell {
-    // ARGB? ABGR?
+pub enum Cell {
     #[default]
-    Dead = 0xFF_00_00_00,
-    Live = 0xFF_00_00_FF,
+    Dead = 0x00_00_00_FF,
+    /// Live, and born on the most recent tick.
+    Newborn = 0xFF_FF_00_FF,
+    Alive = 0x00_00_FF_FF,
+    /// Dead, and died on the most recent tick.
+    Dying = 0xFF_00_00_FF,
+    /// Wireworld electron head.
+    Head = 0x00_80_FF_FF,
+    /// Wireworld electron tail.
+    Tail = 0xFF_60_00_FF,
+    /// Wireworld conductor.
+    Conductor = 0xFF_C0_00_FF,
+    /// Brian's Brain firing cell.
+    On = 0xFF_FF_FF_FF,
+    /// Brian's Brain cell recovering after firing.
+    Refractory = 0x40_40_FF_FF,
+}
+
+impl Cell {
+    /// Whether the cell counts as live for rule evaluation.
+    pub fn is_live(self) -> bool {
+        matches!(self, Cell::Alive | Cell::Newborn | Cell::On)
+    }
+
+    /// The cell's color as a CSS color string.
+    fn css_color(self) -> String {
+        format!("#{:08x}", self as u32)
+    }
+
+    /// The cell to display after a transition between logical states.
+    fn transition(was_live: bool, is_live: bool) -> Cell {
+        match (was_live, is_live) {
+            (true, true) => Cell::Alive,
+            (true, false) => Cell::Dying,
+            (false, true) => Cell::Newborn,
+            (false, false) => Cell::Dead,
+        }
+    }
 }
 
 #[wasm_bindgen]
@@ -20,21 +127,114 @@ pub struct Universe {
     width: usize,
     height: usize,
     content: Vec<Cell>,
+    /// The number of live cells in `content`, kept up to date by every write.
+    live_count: usize,
+    rule: Rule,
+    topology: Topology,
+    log: Option<Arc<Mutex<ReplayLog>>>,
+    generation: u64,
+    /// Selected region, as (x, y, width, height).
+    selection: Option<(usize, usize, usize, usize)>,
+    /// Ticks each cell has been continuously live, if age tracking is enabled.
+    ages: Option<Vec<u32>>,
+    /// Ticks after which each cell was live, if heat map tracking is enabled.
+    visits: Option<Vec<u32>>,
+    /// Previous generations for `undo`, newest last.
+    history: VecDeque<Vec<Cell>>,
+    undo_capacity: usize,
+    /// The generation before the most recent tick, for `step_back`.
+    prev_content: Option<Vec<Cell>>,
+    step_back_enabled: bool,
+    /// Live cell counts of recent generations, oldest first.
+    population_history: VecDeque<usize>,
+    /// Per-cell overrides applied after each tick; see `set_stencil`.
+    stencil: Option<Vec<u8>>,
+    auto_expand: Option<transform::AutoExpand>,
+    log_level: LogLevel,
+    /// Whether `content` is stale and needs a tick; see `mark_dirty`.
+    dirty: bool,
+    /// Whether `render2d` calls `ensure_computed` first.
+    auto_compute: bool,
+    /// The Gray-Scott model, if it has replaced the rule; see `set_rule_gray_scott`.
+    gray_scott: Option<reaction::GrayScott>,
+    /// Each cell's U and V concentrations, while `gray_scott` is set.
+    content_f32: Vec<(f32, f32)>,
+    /// Recent generations for `render2d_trail`, if enabled.
+    trail: Option<trail::TrailBuffer>,
+    /// Where the pattern sits in a larger scene, if read from RLE with a position.
+    position: Option<(i64, i64)>,
+    /// When `render2d_with_stats_overlay` last ran, in ms, and the generation
+    /// then, for its ticks-per-second figure.
+    overlay_sample: Option<(f64, u64)>,
+    /// A byte view of the SharedArrayBuffer this universe syncs with, if any.
+    shared: Option<js_sys::Uint8Array>,
+    /// Whether `render2d_viewport` and `render2d_tiled` mark the universe's edges.
+    seam_visualization: bool,
+    render_config: render::RenderConfig,
 }
 
+#[wasm_bindgen]
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
-struct Coord {
-    y: usize,
-    x: usize,
+pub struct Coord {
+    #[wasm_bindgen(skip)]
+    pub y: usize,
+    #[wasm_bindgen(skip)]
+    pub x: usize,
+}
+
+#[wasm_bindgen]
+impl Coord {
+    #[wasm_bindgen(constructor)]
+    pub fn new(x: u32, y: u32) -> Coord {
+        Coord {
+            y: y as usize,
+            x: x as usize,
+        }
+    }
+
+    #[wasm_bindgen(js_name = x)]
+    pub fn get_x(&self) -> u32 {
+        self.x as u32
+    }
+    #[wasm_bindgen(js_name = y)]
+    pub fn get_y(&self) -> u32 {
+        self.y as u32
+    }
+
+    /// The 8 neighbors of this coordinate in a `width` x `height` torus.
+    pub fn neighbors_vec(&self, width: u32, height: u32) -> Vec<Coord> {
+        self.neighbors(width as usize, height as usize, Topology::Torus)
+            .collect()
+    }
+
+    /// Structural equality, since `PartialEq` isn't visible from JS.
+    #[wasm_bindgen(js_name = eq)]
+    pub fn equals(&self, other: &Coord) -> bool {
+        self == other
+    }
 }
 
 impl Coord {
-    fn neighbors(&self, width: usize, height: usize) -> impl Iterator<Item = Coord> {
+    /// The coordinate of the `index`th cell of a row-major grid.
+    fn from_index(index: usize, width: usize) -> Self {
+        Coord {
+            y: index / width,
+            x: index % width,
+        }
+    }
+
+    fn neighbors(
+        &self,
+        width: usize,
+        height: usize,
+        topology: Topology,
+    ) -> impl Iterator<Item = Coord> {
         Neighbors {
             center: *self,
             n: 0,
             width,
             height,
+            topology,
         }
     }
 }
@@ -44,6 +244,7 @@ struct Neighbors {
     n: usize,
     width: usize,
     height: usize,
+    topology: Topology,
 }
 
 impl Iterator for Neighbors {
@@ -55,29 +256,50 @@ impl Iterator for Neighbors {
         3 4 5
         6 7 8
          */
-        let next = match self.n {
-            3 => 4,
-            8 => return None,
-            n => n + 1,
-        };
+        while self.n < 9 {
+            let n = self.n;
+            self.n += 1;
+            if n == 4 {
+                continue;
+            }
+            let x = self.center.x as isize + (n % 3) as isize - 1;
+            let y = self.center.y as isize + (n / 3) as isize - 1;
+            if let Some(coord) = self.topology.resolve(x, y, self.width, self.height) {
+                return Some(coord);
+            }
+        }
+        None
+    }
+}
 
-        let yoff = match self.n {
-            0 | 1 | 2 => self.height - 1,
-            3 | 4 | 5 => 0,
-            6 | 7 | 8 => 1,
-            _ => 0,
-        };
-        let xoff = match self.n {
-            0 | 3 | 6 => self.width - 1,
-            1 | 4 | 7 => 0,
-            2 | 5 | 8 => 1,
-            _ => 0,
-        };
-        self.n = next;
-        Some(Coord {
-            y: (self.center.y + yoff) % self.height,
-            x: (self.center.x + xoff) % self.width,
-        })
+/// The cells on the Bresenham line from `from` to `to`, inclusive.
+fn line(from: Coord, to: Coord) -> Vec<Coord> {
+    let (mut x, mut y) = (from.x as isize, from.y as isize);
+    let (x2, y2) = (to.x as isize, to.y as isize);
+    let dx = (x2 - x).abs();
+    let dy = -(y2 - y).abs();
+    let sx = if x < x2 { 1 } else { -1 };
+    let sy = if y < y2 { 1 } else { -1 };
+    let mut err = dx + dy;
+
+    let mut coords = Vec::new();
+    loop {
+        coords.push(Coord {
+            y: y as usize,
+            x: x as usize,
+        });
+        if x == x2 && y == y2 {
+            return coords;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
     }
 }
 
@@ -91,6 +313,8 @@ impl Index<Coord> for Universe {
     }
 }
 
+/// Writing through `IndexMut` doesn't update the cached live count;
+/// prefer `set_cell`, or `Universe::put` within the crate.
 impl IndexMut<Coord> for Universe {
     fn index_mut(&mut self, index: Coord) -> &mut Self::Output {
         let row_start = (index.y % self.height) * self.width;
@@ -101,19 +325,15 @@ impl IndexMut<Coord> for Universe {
 
 #[wasm_bindgen]
 impl Universe {
-    pub fn render2d(&self, canvas: &CanvasRenderingContext2d) -> Result<(), JsValue>{
-        let data_slice : &[u8]= {
-            let ptr = self.content.as_ptr() as *const u32 as *const u8;
-            let len = self.content.len() * (std::mem::size_of::<u32>() / std::mem::size_of::<u8>());
-            unsafe {
-                std::slice::from_raw_parts(ptr, len)
-            }
-        };
-        console_log(&format!("data slice: {}", data_slice.len()));
-        console_log(&format!("want: {}", self.width * self.height * 4));
-        assert_eq!(data_slice.len(), self.width * self.height * 4);
-        let data = ImageData::new_with_u8_clamped_array_and_sh(wasm_bindgen::Clamped(data_slice), self.width as u32, self.height as u32)?;
-        canvas.put_image_data(&data, 0.0, 0.0)
+    /// Draw the universe, one pixel per cell.
+    ///
+    /// If `set_auto_compute(true)` is in effect, a pending tick (see `mark_dirty`) is computed
+    /// first.
+    pub fn render2d(&mut self, canvas: &CanvasRenderingContext2d) -> Result<(), JsValue> {
+        if self.auto_compute {
+            self.ensure_computed();
+        }
+        self.put_pixels(canvas, &self.display_pixels())
     }
 
     pub fn render(&self) -> String {
@@ -126,100 +346,1083 @@ impl Universe {
     pub fn get_height(&self) -> usize {
         self.height
     }
+    /// The number of live cells.
+    pub fn live_cell_count(&self) -> usize {
+        self.live_count
+    }
+    /// The number of ticks since the universe was created.
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
 
     /// Create a new Universe of the given dimensions.
     /// The Universe renders into the provided buffer.
+    ///
+    /// Panics if the universe would be empty or exceed `MAX_CELLS`; see `try_new`.
     pub fn new(width: usize, height: usize) -> Self {
-        let mut content = Vec::new();
-        content.resize(width * height, Default::default());
+        Universe::try_new(width, height)
+            .unwrap_or_else(|err| panic!("cannot create universe: {}", err))
+    }
 
-        Universe {
+    /// Create a new Universe of the given dimensions, or an error if either
+    /// is zero or the universe would exceed `MAX_CELLS`.
+    #[wasm_bindgen(js_name = tryNew)]
+    pub fn try_new(width: usize, height: usize) -> Result<Universe, UniverseError> {
+        let content = vec![Cell::default(); checked_cells(width, height)?];
+
+        Ok(Universe {
             width,
             height,
-            content: content,
-        }
+            content,
+            live_count: 0,
+            rule: Rule::default(),
+            topology: Topology::default(),
+            log: None,
+            generation: 0,
+            selection: None,
+            ages: None,
+            visits: None,
+            history: VecDeque::new(),
+            undo_capacity: 0,
+            prev_content: None,
+            step_back_enabled: false,
+            population_history: VecDeque::new(),
+            stencil: None,
+            auto_expand: None,
+            log_level: LogLevel::default(),
+            dirty: false,
+            auto_compute: false,
+            gray_scott: None,
+            content_f32: Vec::new(),
+            trail: None,
+            position: None,
+            overlay_sample: None,
+            shared: None,
+            seam_visualization: false,
+            render_config: render::RenderConfig::default(),
+        })
+    }
+
+    /// Create a Universe from a JS array of strings; see `Universe::from_pattern`.
+    #[wasm_bindgen(js_name = from_pattern)]
+    pub fn from_pattern_js(rows: js_sys::Array) -> Result<Universe, JsValue> {
+        let rows: Vec<String> = rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                row.as_string()
+                    .ok_or_else(|| JsValue::from_str(&format!("row {} is not a string", i)))
+            })
+            .collect::<Result<_, _>>()?;
+        let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+        Ok(Universe::from_pattern(&rows)?)
     }
 
     /// Randomize the content of the universe.
     pub fn randomize(&mut self, seed: u64) {
+        self.record(Op::Randomize { seed });
+        let bits = self.random_bits(seed);
+        self.fill_random_rows(&bits, 0..self.height);
+        self.finish_randomize();
+    }
+
+    /// Randomize the universe so that each cell is alive with probability
+    /// `density`, clamped to [0, 1]. Around 0.37 is the percolation
+    /// threshold for Conway's Life.
+    pub fn randomize_with_density(&mut self, seed: u64, density: f64) {
+        self.record(Op::RandomizeWithDensity { seed, density });
+        let density = density.clamp(0.0, 1.0);
         let mut rng = rand::rngs::SmallRng::seed_from_u64(seed);
-        let mut bits = Vec::new();
-        bits.resize(self.width * self.height / 8, 0);
-        rng.fill_bytes(&mut bits);
+        for cell in self.content.iter_mut() {
+            *cell = if rng.gen::<f64>() < density {
+                Cell::Alive
+            } else {
+                Cell::Dead
+            }
+        }
 
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let idx = y * self.width + x;
-                let idx_word = idx / 8;
-                let idx_bit = idx % 8;
-                self.content[idx] = if bits[idx_word] & (1 << idx_bit) == 0 {
+        self.recount_live();
+        self.log(
+            LogLevel::Info,
+            &format!("randomized, resulting in {} live cells", self.live_count),
+        );
+        self.debug_validate();
+    }
+
+    /// Randomize the universe with fractal gradient noise: cells are alive where
+    /// the noise exceeds `threshold` (noise is roughly in [-1, 1]). Live cells
+    /// form blobs about `1 / frequency` cells across; each of the `octaves`
+    /// adds detail at twice the frequency and `persistence` times the weight.
+    pub fn randomize_perlin(
+        &mut self,
+        seed: u64,
+        octaves: u32,
+        frequency: f64,
+        persistence: f64,
+        threshold: f64,
+    ) {
+        self.record(Op::RandomizePerlin {
+            seed,
+            octaves,
+            frequency,
+            persistence,
+            threshold,
+        });
+        let noise = noise::GradientNoise::new(seed);
+        for (i, cell) in self.content.iter_mut().enumerate() {
+            let here = Coord::from_index(i, self.width);
+            let value = noise.fbm(
+                here.x as f64,
+                here.y as f64,
+                octaves,
+                frequency,
+                persistence,
+            );
+            *cell = if value > threshold {
+                Cell::Alive
+            } else {
+                Cell::Dead
+            };
+        }
+        self.finish_randomize();
+    }
+
+    /// Toggle each cell within distance `radius` of (cx, cy) with probability
+    /// `density`, clamped to [0, 1]. The circle wraps around the edges, and
+    /// each cell in it is considered once even if the circle is wider than the
+    /// universe. A radius of 0 covers only the center cell.
+    pub fn perturb(&mut self, cx: usize, cy: usize, radius: usize, density: f64, seed: u64) {
+        self.record(Op::Perturb {
+            cx,
+            cy,
+            radius,
+            density,
+            seed,
+        });
+        let density = density.clamp(0.0, 1.0);
+        // A circle this wide already covers the whole universe.
+        let r = radius.min(self.width.max(self.height)) as isize;
+        let mut circle: Vec<Coord> = (-r..=r)
+            .flat_map(|dy| (-r..=r).map(move |dx| (dx, dy)))
+            .filter(|(dx, dy)| dx * dx + dy * dy <= r * r)
+            .map(|(dx, dy)| Coord {
+                y: (cy as isize + dy).rem_euclid(self.height as isize) as usize,
+                x: (cx as isize + dx).rem_euclid(self.width as isize) as usize,
+            })
+            .collect();
+        circle.sort();
+        circle.dedup();
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(seed);
+        for here in circle {
+            if rng.gen::<f64>() < density {
+                let toggled = if self[here].is_live() {
                     Cell::Dead
                 } else {
-                    Cell::Live
+                    Cell::Alive
+                };
+                self.put(here, toggled);
+            }
+        }
+        self.debug_validate();
+    }
+
+    /// Set how the edges of the universe connect: "torus", "bounded", "cylinder-h",
+    /// "cylinder-v", "moebius", or "klein".
+    pub fn set_topology(&mut self, topology: &str) -> Result<(), UniverseError> {
+        self.topology = Topology::parse(topology)?;
+        self.record(Op::SetTopology(topology.to_owned()));
+        Ok(())
+    }
+
+    /// Set the automaton: "wireworld", "brians-brain", or a Life-like rule
+    /// in B/S notation, such as "B3/S23".
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), UniverseError> {
+        self.rule = Rule::parse(rule)?;
+        self.end_gray_scott();
+        self.record(Op::SetRule(rule.to_owned()));
+        Ok(())
+    }
+
+    /// Set a Life-like rule from its 18-entry transition table;
+    /// see `Rule::from_table`.
+    pub fn set_rule_table(&mut self, table: js_sys::Uint8Array) -> Result<(), JsValue> {
+        let mut entries = [0u8; 18];
+        if table.length() as usize != entries.len() {
+            return Err(js_sys::Error::new("rule table must have 18 entries").into());
+        }
+        table.copy_to(&mut entries);
+        self.rule = Rule::from_table(&entries);
+        self.end_gray_scott();
+        self.record(Op::SetRule(self.rule.to_string()));
+        Ok(())
+    }
+
+    /// Set the cell at (x, y) live or dead.
+    pub fn set_cell(&mut self, x: usize, y: usize, alive: bool) {
+        self.record(Op::SetCell { x, y, alive });
+        self.put(Coord { y, x }, if alive { Cell::Alive } else { Cell::Dead });
+        self.debug_validate();
+    }
+
+    /// Whether the cell at `coord` is alive.
+    pub fn cell_at_coord(&self, coord: &Coord) -> bool {
+        self[*coord].is_live()
+    }
+
+    /// Set the cell at `coord` live or dead; see `set_cell`.
+    pub fn set_cell_at_coord(&mut self, coord: &Coord, alive: bool) {
+        self.set_cell(coord.x, coord.y, alive);
+    }
+
+    /// Flip the cell at (x, y) between alive and dead.
+    pub fn toggle_cell(&mut self, x: usize, y: usize) {
+        let alive = !self[Coord { y, x }].is_live();
+        self.set_cell(x, y, alive);
+    }
+
+    /// Kill every cell.
+    pub fn clear_all(&mut self) {
+        self.set_cells_bulk(self.live_coords(), false);
+    }
+
+    /// Set many cells live or dead; `coords` is a flat list of (x, y) pairs.
+    pub fn set_cells_bulk(&mut self, coords: Vec<u32>, alive: bool) {
+        let cell = if alive { Cell::Alive } else { Cell::Dead };
+        for pair in coords.chunks_exact(2) {
+            self.put(
+                Coord {
+                    y: pair[1] as usize,
+                    x: pair[0] as usize,
+                },
+                cell,
+            );
+        }
+        self.record(Op::SetCells { coords, alive });
+        self.debug_validate();
+    }
+
+    /// Change the dimensions of the universe, keeping the content pinned to `anchor`:
+    /// "top-left", "top", "top-right", "left", "center", "right",
+    /// "bottom-left", "bottom", or "bottom-right".
+    /// Content that no longer fits is discarded; new space is dead.
+    pub fn resize(
+        &mut self,
+        width: usize,
+        height: usize,
+        anchor: &str,
+    ) -> Result<(), UniverseError> {
+        let (ax, ay) = match anchor {
+            "top-left" => (0, 0),
+            "top" => (1, 0),
+            "top-right" => (2, 0),
+            "left" => (0, 1),
+            "center" => (1, 1),
+            "right" => (2, 1),
+            "bottom-left" => (0, 2),
+            "bottom" => (1, 2),
+            "bottom-right" => (2, 2),
+            _ => return Err(UniverseError::InvalidAnchor(anchor.to_owned())),
+        };
+        // Where the old content's origin lands in the new universe.
+        let dx = (width as isize - self.width as isize) * ax / 2;
+        let dy = (height as isize - self.height as isize) * ay / 2;
+
+        let mut resized = vec![Cell::default(); checked_cells(width, height)?];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (nx, ny) = (x as isize + dx, y as isize + dy);
+                if nx >= 0 && ny >= 0 && (nx as usize) < width && (ny as usize) < height {
+                    resized[ny as usize * width + nx as usize] = self[Coord { y, x }];
                 }
             }
         }
+        self.replace_content(width, height, resized);
+        self.record(Op::Resize {
+            w: width,
+            h: height,
+            anchor: anchor.to_owned(),
+        });
+        Ok(())
+    }
+
+    /// Rotate the content a quarter turn clockwise, swapping width and height.
+    /// Fails on non-orientable topologies.
+    pub fn rotate_cw(&mut self) -> Result<(), UniverseError> {
+        self.require_orientable("rotate_cw")?;
+        let (width, height) = (self.height, self.width);
+        let mut content = Vec::with_capacity(self.content.len());
+        for y in 0..height {
+            for x in 0..width {
+                content.push(
+                    self[Coord {
+                        y: self.height - 1 - x,
+                        x: y,
+                    }],
+                );
+            }
+        }
+        self.replace_content(width, height, content);
+        self.topology = self.topology.rotated();
+        self.record(Op::RotateCw);
+        Ok(())
+    }
+
+    /// Mirror the content left-to-right.
+    /// Fails on non-orientable topologies.
+    pub fn flip_horizontal(&mut self) -> Result<(), UniverseError> {
+        self.require_orientable("flip_horizontal")?;
+        for row in self.content.chunks_mut(self.width) {
+            row.reverse();
+        }
+        if let Some(ages) = &mut self.ages {
+            for row in ages.chunks_mut(self.width) {
+                row.reverse();
+            }
+        }
+        if let Some(visits) = &mut self.visits {
+            for row in visits.chunks_mut(self.width) {
+                row.reverse();
+            }
+        }
+        self.record(Op::FlipHorizontal);
+        Ok(())
+    }
 
-        let c = self.content.iter().filter(|x| **x == Cell::Live).count();
-        console_log(&format!("randomized, resulting in {} live cells", c));
+    /// Start or stop tracking how many ticks each cell has been live.
+    pub fn set_track_age(&mut self, enabled: bool) {
+        self.record(Op::SetTrackAge(enabled));
+        self.ages = if enabled {
+            Some(vec![0; self.content.len()])
+        } else {
+            None
+        };
+    }
+
+    /// The number of ticks the cell at (x, y) has been continuously live;
+    /// always 0 unless age tracking is enabled.
+    pub fn get_age(&self, x: usize, y: usize) -> u32 {
+        let here = Coord {
+            y: y % self.height,
+            x: x % self.width,
+        };
+        self.ages
+            .as_ref()
+            .map_or(0, |ages| ages[here.y * self.width + here.x])
+    }
+
+    /// Switch to the Wireworld automaton.
+    /// Empty cells stay empty; draw conductors with `draw_wire`.
+    pub fn set_rule_wireworld(&mut self) {
+        self.rule = Rule::Wireworld;
+        self.end_gray_scott();
+        self.record(Op::SetRule("wireworld".to_owned()));
+    }
+
+    /// Switch to the Brian's Brain automaton.
+    /// Live cells are treated as firing.
+    pub fn set_rule_brians_brain(&mut self) {
+        self.rule = Rule::BriansBrain;
+        self.end_gray_scott();
+        self.record(Op::SetRule("brians-brain".to_owned()));
+    }
+
+    /// Draw a line of conductor cells from (x1, y1) to (x2, y2), inclusive.
+    pub fn draw_wire(&mut self, x1: usize, y1: usize, x2: usize, y2: usize) {
+        self.record(Op::DrawWire { x1, y1, x2, y2 });
+        for here in line(Coord { y: y1, x: x1 }, Coord { y: y2, x: x2 }) {
+            self.put(here, Cell::Conductor);
+        }
+        self.debug_validate();
+    }
+
+    /// Place an electron (head and trailing tail) on existing wire.
+    pub fn place_electron(&mut self, head_x: usize, head_y: usize, tail_x: usize, tail_y: usize) {
+        self.record(Op::PlaceElectron {
+            head_x,
+            head_y,
+            tail_x,
+            tail_y,
+        });
+        self.put(
+            Coord {
+                y: head_y,
+                x: head_x,
+            },
+            Cell::Head,
+        );
+        self.put(
+            Coord {
+                y: tail_y,
+                x: tail_x,
+            },
+            Cell::Tail,
+        );
+        self.debug_validate();
     }
 
     /// Tick forward the current state.
     pub fn tick(&mut self) {
-        console_log("advancing by one tick");
+        self.record(Op::Tick);
+        self.step();
+    }
+
+    /// Mark the current state as stale, without computing the next generation yet.
+    ///
+    /// The tick happens on the next `ensure_computed`, so a caller can mark the universe dirty on
+    /// every animation frame and only pay for ticks that are actually drawn.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Tick forward if the state has been marked dirty since the last tick.
+    pub fn ensure_computed(&mut self) {
+        if self.dirty {
+            self.dirty = false;
+            self.tick();
+        }
+    }
+
+    /// Set whether `render2d` calls `ensure_computed` before drawing.
+    pub fn set_auto_compute(&mut self, enabled: bool) {
+        self.auto_compute = enabled;
+    }
+
+    /// Tick forward, returning statistics about the tick.
+    pub fn tick_measured(&mut self) -> TickStats {
+        self.record(Op::Tick);
+        let start = now_ms();
+        let mut stats = self.step();
+        stats.duration_ms = now_ms() - start;
+        stats
+    }
+
+    /// Tick forward `n` times.
+    pub fn tick_n(&mut self, n: usize) {
+        self.record(Op::TickN(n));
+        for _ in 0..n {
+            self.step();
+        }
+    }
+
+    /// Advance only the cells in the `w` x `h` rectangle at (x, y) by one tick
+    /// of `rule`, wrapping around the edges. Neighbors outside the rectangle
+    /// are read but not updated. This doesn't count as a generation. A
+    /// rectangle wider or taller than the universe is clamped to its size.
+    ///
+    /// Each call is a separate step: applying different rules to overlapping
+    /// regions "in the same tick" depends on the order of the calls.
+    pub fn apply_rule_to_region(
+        &mut self,
+        rule: &str,
+        x: usize,
+        y: usize,
+        w: usize,
+        h: usize,
+    ) -> Result<(), UniverseError> {
+        let parsed = Rule::parse(rule)?;
+        self.record(Op::ApplyRuleToRegion {
+            rule: rule.to_owned(),
+            x,
+            y,
+            w,
+            h,
+        });
+        let (x, y) = (x % self.width, y % self.height);
+        let (w, h) = (w.min(self.width), h.min(self.height));
+        let mut updates = Vec::with_capacity(w * h);
+        for ry in y..y + h {
+            for rx in x..x + w {
+                let here = Coord {
+                    y: ry % self.height,
+                    x: rx % self.width,
+                };
+                let neighbors = here
+                    .neighbors(self.width, self.height, self.topology)
+                    .map(|coord| self[coord]);
+                updates.push((here, parsed.next(self[here], neighbors)));
+            }
+        }
+        for (here, cell) in updates {
+            self.put(here, cell);
+        }
+        self.debug_validate();
+        Ok(())
+    }
+
+    /// The live neighbors of (x, y), each weighted by how long it has been
+    /// live: 1 plus its age, so with every age 0 this is the live neighbor
+    /// count. Ages are 0 unless age tracking is enabled; see `set_track_age`.
+    pub fn weighted_neighbor_count(&self, x: usize, y: usize) -> f64 {
+        let here = Coord {
+            y: y % self.height,
+            x: x % self.width,
+        };
+        here.neighbors(self.width, self.height, self.topology)
+            .filter(|&coord| self[coord].is_live())
+            .map(|coord| 1.0 + self.get_age(coord.x, coord.y) as f64)
+            .sum()
+    }
+
+    /// Tick forward under a rule on `weighted_neighbor_count`: a dead cell is
+    /// born if its count is at least `birth_threshold`, and a live cell survives
+    /// if its count is in `survival_min..=survival_max`. Fails unless age
+    /// tracking is enabled.
+    pub fn tick_weighted_rule(
+        &mut self,
+        birth_threshold: f64,
+        survival_min: f64,
+        survival_max: f64,
+    ) -> Result<(), UniverseError> {
+        if self.ages.is_none() {
+            return Err(UniverseError::AgeTrackingRequired {
+                operation: "tick_weighted_rule",
+            });
+        }
+        self.record(Op::TickWeightedRule {
+            birth_threshold,
+            survival_min,
+            survival_max,
+        });
+        self.step_by(|universe, here| {
+            let weight = universe.weighted_neighbor_count(here.x, here.y);
+            let was_live = universe[here].is_live();
+            let is_live = if was_live {
+                (survival_min..=survival_max).contains(&weight)
+            } else {
+                weight >= birth_threshold
+            };
+            Ok::<_, UniverseError>(Cell::transition(was_live, is_live))
+        })?;
+        Ok(())
+    }
+
+    /// Tick forward with the next state of each cell decided by calling
+    /// `rule_fn(is_alive, live_neighbor_count)`, which returns whether the cell
+    /// is live next tick. If `rule_fn` throws, the universe is unchanged and the
+    /// exception is returned. These ticks are not recorded in the replay log.
+    ///
+    /// This is for prototyping rules only: it crosses from Wasm to JS once per
+    /// cell, so a 512x512 universe calls `rule_fn` 262,144 times per tick.
+    pub fn tick_with_rule_fn(&mut self, rule_fn: &js_sys::Function) -> Result<(), JsValue> {
+        self.step_by(|universe, here| {
+            let was_live = universe[here].is_live();
+            let is_live = rule_fn
+                .call2(
+                    &JsValue::NULL,
+                    &JsValue::from_bool(was_live),
+                    &JsValue::from(universe.live_neighbors(here) as u8),
+                )?
+                .is_truthy();
+            Ok::<_, JsValue>(Cell::transition(was_live, is_live))
+        })?;
+        Ok(())
+    }
+}
+
+impl Universe {
+    /// Iterate over `(Coord, Cell)` pairs in row-major order.
+    pub fn cells(&self) -> Cells<'_> {
+        self.into_iter()
+    }
+
+    /// Start recording operations on this universe into `log`.
+    pub fn attach_log(&mut self, log: Arc<Mutex<ReplayLog>>) {
+        self.log = Some(log);
+    }
+
+    pub(crate) fn record(&self, op: Op) {
+        if let Some(log) = &self.log {
+            log.lock().expect("replay log lock poisoned").push(op);
+        }
+    }
+
+    /// Advance the state by one tick, without recording it.
+    /// Returns the births, deaths, and survivors of the tick.
+    fn step(&mut self) -> TickStats {
+        if let Some(stats) = self.step_gray_scott() {
+            return stats;
+        }
+        let stepped = self.step_by(|universe, here| {
+            let neighbors = here
+                .neighbors(universe.width, universe.height, universe.topology)
+                .map(|coord| universe[coord]);
+            Ok::<_, std::convert::Infallible>(universe.rule.next(universe[here], neighbors))
+        });
+        match stepped {
+            Ok(stats) => stats,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Advance the state by one tick, without recording it, with each cell's
+    /// next state given by `next`. If `next` fails, the universe is unchanged.
+    fn step_by<E>(
+        &mut self,
+        mut next: impl FnMut(&Universe, Coord) -> Result<Cell, E>,
+    ) -> Result<TickStats, E> {
+        self.log(LogLevel::Debug, "advancing by one tick");
+        let mut stats = TickStats::default();
         let mut content = Vec::with_capacity(self.content.len());
         for y in 0..self.height {
             for x in 0..self.width {
                 let here = Coord { y, x };
-                let live_count = here
-                    .neighbors(self.width, self.height)
-                    .filter(|coord| self[*coord] == Cell::Live)
-                    .count();
-
-                let next = match (self[here], live_count) {
-                    // Rule 1: loneliness
-                    (Cell::Live, x) if x < 2 => Cell::Dead,
-                    // Rule 3: Overpopulation
-                    (Cell::Live, x) if x > 3 => Cell::Dead,
-                    // Rule 4: Reproduction
-                    (Cell::Dead, x) if x == 3 => Cell::Live,
-                    // Rule 2: Stayin' Alive, or dead-means-dead.
-                    (v, _) => v,
-                };
-
+                let next = next(self, here)?;
+                let next = self.stencilled(content.len(), next);
+                stats.count(self[here].is_live(), next.is_live());
                 content.push(next);
             }
         }
-        let c = self.content.iter().filter(|x| **x == Cell::Live).count();
-        console_log(&format!("tick resulted in {} live cells", c));
+        self.log(
+            LogLevel::Info,
+            &format!(
+                "tick resulted in {} live cells",
+                stats.population_after_u32()
+            ),
+        );
+        self.push_trail();
         std::mem::swap(&mut self.content, &mut content);
+        self.live_count = stats.population_after_u32() as usize;
+        self.push_population(stats.population_before_u32() as usize, self.live_count);
+        self.generation += 1;
+        self.push_history(content);
+
+        if let Some(ages) = &mut self.ages {
+            for (age, cell) in ages.iter_mut().zip(self.content.iter()) {
+                *age = if cell.is_live() { *age + 1 } else { 0 };
+            }
+        }
+        self.count_visits();
+        self.expand_if_near_boundary();
+        self.debug_validate();
+        Ok(stats)
+    }
+
+    /// One random bit per cell, for `randomize`.
+    pub(crate) fn random_bits(&self, seed: u64) -> Vec<u8> {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(seed);
+        let mut bits = vec![0; (self.width * self.height).div_ceil(8)];
+        rng.fill_bytes(&mut bits);
+        bits
+    }
+
+    /// Set each cell in `rows` from its bit in `bits`, without updating the
+    /// live count; call `finish_randomize` once all rows are filled.
+    pub(crate) fn fill_random_rows(&mut self, bits: &[u8], rows: std::ops::Range<usize>) {
+        for y in rows {
+            for x in 0..self.width {
+                let idx = y * self.width + x;
+                let idx_word = idx / 8;
+                let idx_bit = idx % 8;
+                self.content[idx] = if bits[idx_word] & (1 << idx_bit) == 0 {
+                    Cell::Dead
+                } else {
+                    Cell::Alive
+                }
+            }
+        }
+    }
+
+    pub(crate) fn finish_randomize(&mut self) {
+        self.recount_live();
+        self.log(
+            LogLevel::Info,
+            &format!("randomized, resulting in {} live cells", self.live_count),
+        );
+        self.debug_validate();
+    }
+
+    /// Set the cell at `here`, keeping the live count up to date.
+    pub(crate) fn put(&mut self, here: Coord, cell: Cell) {
+        let old = std::mem::replace(&mut self[here], cell);
+        if old.is_live() {
+            self.live_count -= 1;
+        }
+        if cell.is_live() {
+            self.live_count += 1;
+        }
+    }
+
+    /// Recompute the live count after replacing `content` wholesale.
+    pub(crate) fn recount_live(&mut self) {
+        self.live_count = self.content.iter().filter(|cell| cell.is_live()).count();
+    }
+
+    /// The number of live neighbors of `here`, under the current topology.
+    fn live_neighbors(&self, here: Coord) -> usize {
+        self.live_neighbors_under(here, self.topology)
+    }
+
+    /// The number of live neighbors of `here`, as if the universe had `topology`.
+    fn live_neighbors_under(&self, here: Coord, topology: Topology) -> usize {
+        here.neighbors(self.width, self.height, topology)
+            .filter(|coord| self[*coord].is_live())
+            .count()
+    }
+
+    /// Replace the whole grid, resetting state tied to the old layout:
+    /// the selection, cell ages and visit counts, undo and step-back history, population history,
+    /// trail, and stencil. Gray-Scott concentrations are reseeded from the new cells.
+    fn replace_content(&mut self, width: usize, height: usize, content: Vec<Cell>) {
+        debug_assert_eq!(content.len(), width * height);
+        self.width = width;
+        self.height = height;
+        self.content = content;
+        self.recount_live();
+        self.selection = None;
+        if let Some(ages) = &mut self.ages {
+            *ages = vec![0; self.content.len()];
+        }
+        if let Some(visits) = &mut self.visits {
+            *visits = vec![0; self.content.len()];
+        }
+        self.history.clear();
+        self.prev_content = None;
+        self.population_history.clear();
+        if let Some(trail) = &mut self.trail {
+            trail.clear();
+        }
+        self.stencil = None;
+        self.seed_gray_scott();
+        self.debug_validate();
+    }
+
+    fn require_orientable(&self, operation: &'static str) -> Result<(), UniverseError> {
+        if self.topology.is_orientable() {
+            Ok(())
+        } else {
+            Err(UniverseError::UnsupportedTopology { operation })
+        }
+    }
+
+    /// The RGBA pixel data for the universe, one pixel per cell.
+    fn pixels(&self) -> Vec<u8> {
+        self.pixels_with(|_, cell| cell as u32)
+    }
+
+    /// The RGBA pixel data `render2d` draws: the cells, or the Gray-Scott
+    /// concentrations if that simulation is running.
+    pub(crate) fn display_pixels(&self) -> Vec<u8> {
+        if self.gray_scott.is_some() {
+            self.gray_scott_pixels()
+        } else {
+            self.pixels()
+        }
+    }
+
+    /// RGBA pixel data with each cell's `0xRRGGBBAA` color chosen by `color`,
+    /// given the cell's index and state.
+    pub(crate) fn pixels_with(&self, color: impl Fn(usize, Cell) -> u32) -> Vec<u8> {
+        self.content
+            .iter()
+            .enumerate()
+            .flat_map(|(i, cell)| color(i, *cell).to_be_bytes())
+            .collect()
+    }
+
+    /// Draw `data_slice`, from `pixels_with`, at one pixel per cell.
+    pub(crate) fn put_pixels(
+        &self,
+        canvas: &CanvasRenderingContext2d,
+        data_slice: &[u8],
+    ) -> Result<(), JsValue> {
+        self.log(
+            LogLevel::Debug,
+            &format!("data slice: {}", data_slice.len()),
+        );
+        self.log(
+            LogLevel::Debug,
+            &format!("want: {}", self.width * self.height * 4),
+        );
+        assert_eq!(data_slice.len(), self.width * self.height * 4);
+        let data = ImageData::new_with_u8_clamped_array_and_sh(
+            wasm_bindgen::Clamped(data_slice),
+            self.width as u32,
+            self.height as u32,
+        )?;
+        canvas.put_image_data(&data, 0.0, 0.0)
+    }
+
+    /// Create a Universe from a grid of strings, one string per row.
+    ///
+    /// `O`, `*`, `#`, and `1` mark live cells; ` `, `.`, `_`, and `0` mark dead cells.
+    /// The universe is as wide as the longest row; shorter rows are padded with dead cells.
+    pub fn from_pattern(rows: &[&str]) -> Result<Self, UniverseError> {
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        if width == 0 {
+            return Err(UniverseError::EmptyPattern);
+        }
+
+        let mut universe = Universe::try_new(width, rows.len())?;
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                universe.put(
+                    Coord { y, x },
+                    match c {
+                        'O' | '*' | '#' | '1' => Cell::Alive,
+                        ' ' | '.' | '_' | '0' => Cell::Dead,
+                        found => {
+                            return Err(UniverseError::InvalidPatternChar {
+                                row: y,
+                                column: x,
+                                found,
+                            })
+                        }
+                    },
+                );
+            }
+        }
+        Ok(universe)
     }
 }
 
 impl std::fmt::Display for Universe {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        console_log("performing rendering cycle");
-        for y in 0..self.height {
-            let line: String = (0..self.width)
-                .map(|x| match self[Coord { y, x }] {
-                    Cell::Live => '+',
-                    Cell::Dead => ' ',
-                })
-                .collect();
-            write!(f, "{}\n", line)?;
+        self.log(LogLevel::Debug, "performing rendering cycle");
+        for (coord, cell) in self {
+            write!(f, "{}", if cell.is_live() { '+' } else { ' ' })?;
+            if coord.x == self.width - 1 {
+                writeln!(f)?;
+            }
         }
         Ok(())
     }
 }
 
+/// The current time in milliseconds, from `performance.now()` where available.
+pub(crate) fn now_ms() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map(|performance| performance.now())
+        .unwrap_or_else(js_sys::Date::now)
+}
+
 #[wasm_bindgen]
 pub fn get_string() -> String {
     "Hello there!".to_owned()
 }
 
+#[cfg(target_arch = "wasm32")]
 #[wasm_bindgen]
 extern "C" {
     /// Logs to the external console.
     #[wasm_bindgen(js_namespace=console, js_name=log)]
     pub fn console_log(s: &str);
 }
+
+/// Logs to stderr, standing in for the browser console in native builds.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn console_log(s: &str) {
+    eprintln!("{}", s);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The number of horizontally or vertically adjacent pairs of cells, one
+    /// live and one dead, wrapping around the edges: the length of the
+    /// boundary between live and dead regions.
+    pub(crate) fn perimeter(universe: &Universe) -> usize {
+        universe
+            .cells()
+            .map(|(here, cell)| {
+                let live = cell.is_live();
+                let right = Coord {
+                    y: here.y,
+                    x: (here.x + 1) % universe.width,
+                };
+                let below = Coord {
+                    y: (here.y + 1) % universe.height,
+                    x: here.x,
+                };
+                (live != universe[right].is_live()) as usize
+                    + (live != universe[below].is_live()) as usize
+            })
+            .sum()
+    }
+
+    /// A bounded Wireworld universe with conductor wherever `rows` has a `#`.
+    fn wireworld(rows: &[&str]) -> Universe {
+        let mut universe = Universe::new(rows[0].len(), rows.len());
+        universe.set_topology("bounded").unwrap();
+        universe.set_rule_wireworld();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, _) in row.match_indices('#') {
+                universe.draw_wire(x, y, x, y);
+            }
+        }
+        universe
+    }
+
+    /// Run an AND gate with electrons sent down the chosen inputs, and report
+    /// whether one came out of the output.
+    fn and_gate(a: bool, b: bool) -> bool {
+        // Input A enters along the top row, input B along the bottom row, and
+        // the output leaves to the right along row 3.
+        let mut universe = wireworld(&[
+            "########.......#........",
+            "........#....###........",
+            ".........###.###........",
+            ".........######.########",
+            ".........###..##........",
+            "........#..#............",
+            "########................",
+        ]);
+        if a {
+            universe.place_electron(2, 0, 1, 0);
+        }
+        if b {
+            universe.place_electron(2, 6, 1, 6);
+        }
+        let output = Coord { y: 3, x: 22 };
+        let fired = (0..100).fold(false, |fired, _| {
+            universe.tick();
+            fired || universe[output] == Cell::Head
+        });
+        // The gate must settle rather than keep emitting.
+        assert!(universe.cells().all(|(_, cell)| cell != Cell::Head));
+        fired
+    }
+
+    #[test]
+    fn wireworld_and_gate() {
+        assert!(!and_gate(false, false));
+        assert!(!and_gate(true, false));
+        assert!(!and_gate(false, true));
+        assert!(and_gate(true, true));
+    }
+
+    #[test]
+    fn wireworld_electron_travels_along_wire() {
+        let mut universe = Universe::new(10, 3);
+        universe.set_rule_wireworld();
+        universe.draw_wire(0, 1, 9, 1);
+        universe.place_electron(2, 1, 1, 1);
+        universe.tick();
+        assert_eq!(universe[Coord { y: 1, x: 3 }], Cell::Head);
+        assert_eq!(universe[Coord { y: 1, x: 2 }], Cell::Tail);
+        assert_eq!(universe[Coord { y: 1, x: 1 }], Cell::Conductor);
+        assert_eq!(universe[Coord { y: 0, x: 3 }], Cell::Dead);
+    }
+
+    #[test]
+    fn brians_brain_cell_fires_then_rests() {
+        let mut universe = Universe::from_pattern(&["....", ".##.", "....", "...."]).unwrap();
+        universe.set_rule_brians_brain();
+        universe.tick();
+        assert_eq!(universe[Coord { y: 1, x: 1 }], Cell::Refractory);
+        assert_eq!(universe[Coord { y: 0, x: 1 }], Cell::On);
+        universe.tick();
+        assert_eq!(universe[Coord { y: 1, x: 1 }], Cell::Dead);
+        assert_eq!(universe[Coord { y: 0, x: 1 }], Cell::Refractory);
+    }
+
+    #[test]
+    fn brians_brain_random_universe_stays_active() {
+        let mut universe = Universe::new(100, 100);
+        universe.randomize(7);
+        universe.set_rule_brians_brain();
+        for _ in 0..1000 {
+            universe.tick();
+            assert!(universe.live_cell_count() > 0);
+        }
+    }
+
+    #[test]
+    fn randomize_with_density_extremes() {
+        let mut universe = Universe::new(16, 12);
+        universe.randomize_with_density(5, 0.0);
+        assert_eq!(universe.live_cell_count(), 0);
+        universe.randomize_with_density(5, 1.0);
+        assert_eq!(universe.live_cell_count(), 16 * 12);
+        // Out-of-range densities are clamped.
+        universe.randomize_with_density(5, -1.0);
+        assert_eq!(universe.live_cell_count(), 0);
+        universe.randomize_with_density(5, 2.0);
+        assert_eq!(universe.live_cell_count(), 16 * 12);
+    }
+
+    #[test]
+    fn randomize_with_density_is_seeded() {
+        let mut a = Universe::new(32, 32);
+        let mut b = Universe::new(32, 32);
+        a.randomize_with_density(9, 0.37);
+        b.randomize_with_density(9, 0.37);
+        assert_eq!(a.live_coords(), b.live_coords());
+        let fraction = a.live_cell_count() as f64 / (32.0 * 32.0);
+        assert!((fraction - 0.37).abs() < 0.05, "{}", fraction);
+    }
+
+    #[test]
+    fn randomize_perlin_is_smoother_than_randomize() {
+        let mut noisy = Universe::new(64, 64);
+        noisy.randomize(11);
+        let mut smooth = Universe::new(64, 64);
+        smooth.randomize_perlin(11, 1, 0.1, 0.5, 0.0);
+        let live = smooth.live_cell_count();
+        assert!(live > 64 * 64 / 5 && live < 64 * 64 * 4 / 5, "{}", live);
+        assert!(perimeter(&smooth) * 3 < perimeter(&noisy));
+    }
+
+    #[test]
+    fn randomize_perlin_is_seeded() {
+        let mut a = Universe::new(32, 32);
+        let mut b = Universe::new(32, 32);
+        a.randomize_perlin(4, 3, 0.05, 0.5, 0.1);
+        b.randomize_perlin(4, 3, 0.05, 0.5, 0.1);
+        assert_eq!(a.live_coords(), b.live_coords());
+        b.randomize_perlin(5, 3, 0.05, 0.5, 0.1);
+        assert_ne!(a.live_coords(), b.live_coords());
+    }
+
+    #[test]
+    fn perturb_with_zero_radius_flips_only_center() {
+        for seed in 0..16 {
+            let mut universe = Universe::new(9, 9);
+            universe.randomize(seed);
+            let center = Coord { y: 4, x: 6 };
+            let mut before: Vec<bool> = universe.content.iter().map(|c| c.is_live()).collect();
+            universe.perturb(center.x, center.y, 0, 1.0, seed);
+            let index = center.y * 9 + center.x;
+            before[index] = !before[index];
+            let after: Vec<bool> = universe.content.iter().map(|c| c.is_live()).collect();
+            assert_eq!(after, before);
+        }
+    }
+
+    #[test]
+    fn perturb_wraps_around_edges() {
+        let mut universe = Universe::new(10, 10);
+        universe.perturb(0, 0, 1, 1.0, 3);
+        // The center and its four orthogonal neighbors, two of them wrapped.
+        assert_eq!(universe.live_cell_count(), 5);
+        for (x, y) in [(0, 0), (1, 0), (9, 0), (0, 1), (0, 9)] {
+            assert!(universe[Coord { y, x }].is_live());
+        }
+    }
+
+    #[test]
+    fn perturb_with_zero_density_changes_nothing() {
+        let mut universe = Universe::new(10, 10);
+        universe.randomize(2);
+        let before = universe.live_coords();
+        universe.perturb(5, 5, 4, 0.0, 1);
+        assert_eq!(universe.live_coords(), before);
+    }
+
+    #[test]
+    fn perturb_with_huge_radius_covers_universe_once() {
+        let mut huge = Universe::new(12, 7);
+        huge.perturb(3, 2, usize::MAX, 0.5, 9);
+        let mut covering = Universe::new(12, 7);
+        covering.perturb(3, 2, 12, 0.5, 9);
+        assert_eq!(huge.live_coords(), covering.live_coords());
+        let mut full = Universe::new(12, 7);
+        full.perturb(3, 2, usize::MAX, 1.0, 9);
+        assert_eq!(full.live_cell_count(), 12 * 7);
+    }
+}
@@ -0,0 +1,180 @@
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+/// Built-in patterns, all under B3/S23: name, period (0 if it never repeats),
+/// smallest and largest population over a period, width, height, and RLE body.
+/// Spaceships' periods are the ticks until they repeat, displaced.
+#[rustfmt::skip]
+const PATTERNS: &[(&str, u32, usize, usize, usize, usize, &str)] = &[
+    // Still lifes.
+    ("block", 1, 4, 4, 2, 2, "2o$2o!"),
+    ("beehive", 1, 6, 6, 4, 3, "b2o$o2bo$b2o!"),
+    ("loaf", 1, 7, 7, 4, 4, "b2o$o2bo$bobo$2bo!"),
+    ("boat", 1, 5, 5, 3, 3, "2o$obo$bo!"),
+    ("tub", 1, 4, 4, 3, 3, "bo$obo$bo!"),
+    ("ship", 1, 6, 6, 3, 3, "2o$obo$b2o!"),
+    ("pond", 1, 8, 8, 4, 4, "b2o$o2bo$o2bo$b2o!"),
+    ("long boat", 1, 7, 7, 4, 4, "2o$obo$bobo$2bo!"),
+    ("barge", 1, 6, 6, 4, 4, "bo$obo$bobo$2bo!"),
+    ("snake", 1, 6, 6, 4, 2, "2obo$ob2o!"),
+    ("eater 1", 1, 7, 7, 4, 4, "2o$obo$2bo$2b2o!"),
+    ("aircraft carrier", 1, 6, 6, 4, 3, "2o$o2bo$2b2o!"),
+    ("long ship", 1, 8, 8, 4, 4, "2o$obo$bobo$2b2o!"),
+    ("mango", 1, 8, 8, 5, 4, "b2o$o2bo$bo2bo$2b2o!"),
+    ("bi-block", 1, 8, 8, 5, 2, "2ob2o$2ob2o!"),
+    ("hat", 1, 9, 9, 5, 4, "2bo$bobo$bobo$2ob2o!"),
+    ("canoe", 1, 8, 8, 5, 5, "3b2o$4bo$3bo$obo$2o!"),
+    ("ship-tie", 1, 12, 12, 6, 6, "2o$obo$b2o$3b2o$3bobo$4b2o!"),
+    ("integral sign", 1, 9, 9, 5, 5, "2o$obo$2bo$2bobo$3b2o!"),
+    // Oscillators.
+    ("blinker", 2, 3, 3, 3, 1, "3o!"),
+    ("toad", 2, 6, 6, 4, 2, "b3o$3o!"),
+    ("beacon", 2, 6, 8, 4, 4, "2o$2o$2b2o$2b2o!"),
+    ("clock", 2, 6, 6, 4, 4, "2bo$obo$bobo$bo!"),
+    ("bipole", 2, 8, 8, 5, 5, "2o$obo2$2bobo$3b2o!"),
+    ("quad", 2, 16, 16, 6, 6, "2o2b2o$o2bobo$bo$4bo$obo2bo$2o2b2o!"),
+    ("spark coil", 2, 18, 20, 8, 5, "2o4b2o$obo2bobo$2bo2bo$obo2bobo$2o4b2o!"),
+    ("test tube baby", 2, 14, 16, 8, 5, "2o4b2o$obo2bobo$2bo2bo$2bo2bo$3b2o!"),
+    ("tripole", 2, 10, 10, 7, 7, "2o$obo2$2bobo2$4bobo$5b2o!"),
+    ("pulsar", 3, 48, 72, 13, 13, "2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!"),
+    ("cross", 3, 28, 28, 8, 8, "2b4o$2bo2bo$3o2b3o$o6bo$o6bo$3o2b3o$2bo2bo$2b4o!"),
+    ("caterer", 3, 12, 16, 8, 6, "2bo$o3b4o$o3bo$o$3bo$b2o!"),
+    ("mold", 4, 12, 14, 6, 6, "3b2o$2bo2bo$o2bobo$4bo$ob2o$bo!"),
+    ("octagon 2", 5, 16, 24, 8, 8, "3b2o$2bo2bo$bo4bo$o6bo$o6bo$bo4bo$2bo2bo$3b2o!"),
+    ("fumarole", 5, 18, 22, 8, 7, "3b2o$bo4bo$bo4bo$bo4bo$2bo2bo$obo2bobo$2o4b2o!"),
+    ("unix", 6, 16, 24, 8, 8, "b2o$b2o2$bo$obo$o2bo2b2o$4bob2o$2b2o!"),
+    ("figure eight", 8, 12, 26, 6, 6, "3o$3o$3o$3b3o$3b3o$3b3o!"),
+    ("kok's galaxy", 8, 28, 64, 9, 9, "2ob6o$2ob6o$2o$2o5b2o$2o5b2o$2o5b2o$7b2o$6ob2o$6ob2o!"),
+    ("tumbler", 14, 16, 22, 9, 5, "bo5bo$obo3bobo$o2bobo2bo$2bo3bo$2b2ob2o!"),
+    ("pentadecathlon", 15, 12, 40, 10, 3, "2bo4bo$2ob4ob2o$2bo4bo!"),
+    // Spaceships.
+    ("glider", 4, 5, 5, 3, 3, "bo$2bo$3o!"),
+    ("lightweight spaceship", 4, 9, 12, 5, 4, "bo2bo$o$o3bo$4o!"),
+    ("middleweight spaceship", 4, 11, 15, 6, 5, "3bo$bo3bo$o$o4bo$5o!"),
+    ("heavyweight spaceship", 4, 13, 18, 7, 5, "3b2o$bo4bo$o$o5bo$6o!"),
+    // Methuselahs.
+    ("r-pentomino", 0, 5, 5, 3, 3, "b2o$2o$bo!"),
+    ("acorn", 0, 7, 7, 7, 3, "bo$3bo$2o2b3o!"),
+    ("diehard", 0, 7, 7, 8, 3, "6bo$2o$bo3b3o!"),
+    ("b-heptomino", 0, 7, 7, 4, 3, "ob2o$3o$bo!"),
+    ("pi-heptomino", 0, 7, 7, 3, 3, "3o$obo$obo!"),
+    ("thunderbird", 0, 6, 6, 3, 5, "3o2$bo$bo$bo!"),
+];
+
+/// A pattern in a `PatternLibrary`.
+#[wasm_bindgen]
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct PatternEntry {
+    name: String,
+    period: u32,
+    min_pop: usize,
+    max_pop: usize,
+    width: usize,
+    height: usize,
+    rle: String,
+}
+
+#[wasm_bindgen]
+impl PatternEntry {
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+    /// The number of ticks before the pattern repeats, or 0 if it never does.
+    pub fn period(&self) -> u32 {
+        self.period
+    }
+    pub fn min_pop(&self) -> usize {
+        self.min_pop
+    }
+    pub fn max_pop(&self) -> usize {
+        self.max_pop
+    }
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    /// The pattern in Run Length Encoded format, with a header.
+    pub fn rle(&self) -> String {
+        self.rle.clone()
+    }
+}
+
+/// A searchable collection of well-known patterns.
+#[wasm_bindgen]
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct PatternLibrary {
+    entries: Vec<PatternEntry>,
+}
+
+impl Default for PatternLibrary {
+    /// The built-in classic still lifes, oscillators, spaceships, and methuselahs.
+    fn default() -> Self {
+        let entries = PATTERNS
+            .iter()
+            .map(
+                |&(name, period, min_pop, max_pop, width, height, body)| PatternEntry {
+                    name: name.to_owned(),
+                    period,
+                    min_pop,
+                    max_pop,
+                    width,
+                    height,
+                    rle: format!("x = {}, y = {}, rule = B3/S23\n{}", width, height, body),
+                },
+            )
+            .collect();
+        PatternLibrary { entries }
+    }
+}
+
+#[wasm_bindgen]
+impl PatternLibrary {
+    /// The built-in library; see `PatternLibrary::default`.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The patterns with a period in `min_period..=max_period`, at most
+    /// `max_pop` live cells at any point, and neither dimension over `max_dim`.
+    pub fn search(
+        &self,
+        min_period: u32,
+        max_period: u32,
+        max_pop: usize,
+        max_dim: usize,
+    ) -> Vec<PatternEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| {
+                (min_period..=max_period).contains(&entry.period)
+                    && entry.max_pop <= max_pop
+                    && entry.width.max(entry.height) <= max_dim
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// A Universe containing the named pattern, exactly as large as it is.
+    pub fn get_by_name(&self, name: &str) -> Option<Universe> {
+        let entry = self.entries.iter().find(|entry| entry.name == name)?;
+        Universe::from_rle(&entry.rle).ok()
+    }
+}
+
+impl PatternLibrary {
+    pub fn entries(&self) -> &[PatternEntry] {
+        &self.entries
+    }
+}
@@ -0,0 +1,56 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{console_log, Universe, UniverseError};
+
+/// How much a Universe writes to the console. Each level includes the ones before it.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+pub enum LogLevel {
+    None = 0,
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+}
+
+impl Default for LogLevel {
+    /// Info in debug builds; silent in release builds.
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            LogLevel::Info
+        } else {
+            LogLevel::None
+        }
+    }
+}
+
+impl LogLevel {
+    /// Parse a level name: "none", "error", "warn", "info", or "debug".
+    pub fn parse(name: &str) -> Result<Self, UniverseError> {
+        match name {
+            "none" => Ok(LogLevel::None),
+            "error" => Ok(LogLevel::Error),
+            "warn" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            _ => Err(UniverseError::InvalidLogLevel(name.to_owned())),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Set how much this universe logs; see `LogLevel::parse`.
+    pub fn set_log_level(&mut self, level: &str) -> Result<(), UniverseError> {
+        self.log_level = LogLevel::parse(level)?;
+        Ok(())
+    }
+}
+
+impl Universe {
+    /// Write `message` to the console if `level` is enabled.
+    pub(crate) fn log(&self, level: LogLevel, message: &str) {
+        if level != LogLevel::None && level <= self.log_level {
+            console_log(message);
+        }
+    }
+}
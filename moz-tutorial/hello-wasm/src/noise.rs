@@ -0,0 +1,113 @@
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+/// Two-dimensional gradient noise over a seeded permutation of 256 lattice gradients.
+pub(crate) struct GradientNoise {
+    permutation: [u8; 512],
+}
+
+impl GradientNoise {
+    pub(crate) fn new(seed: u64) -> Self {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(seed);
+        let mut table: Vec<u8> = (0..=255).collect();
+        table.shuffle(&mut rng);
+        let mut permutation = [0; 512];
+        for (i, entry) in permutation.iter_mut().enumerate() {
+            *entry = table[i % 256];
+        }
+        GradientNoise { permutation }
+    }
+
+    /// Noise at (x, y), roughly in [-1, 1]. Zero at every lattice point.
+    pub(crate) fn at(&self, x: f64, y: f64) -> f64 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (xi, yi) = ((x0 as i64 & 255) as usize, (y0 as i64 & 255) as usize);
+        let corner = |dx: usize, dy: usize| {
+            let hash = self.permutation[self.permutation[xi + dx] as usize + yi + dy];
+            gradient(hash, fx - dx as f64, fy - dy as f64)
+        };
+        let (u, v) = (fade(fx), fade(fy));
+        let top = lerp(corner(0, 0), corner(1, 0), u);
+        let bottom = lerp(corner(0, 1), corner(1, 1), u);
+        lerp(top, bottom, v)
+    }
+
+    /// Fractal Brownian motion: `octaves` layers of noise, each at twice the
+    /// frequency and `persistence` times the amplitude of the one before,
+    /// normalized to roughly [-1, 1].
+    pub(crate) fn fbm(
+        &self,
+        x: f64,
+        y: f64,
+        octaves: u32,
+        frequency: f64,
+        persistence: f64,
+    ) -> f64 {
+        let (mut total, mut max, mut amplitude, mut frequency) = (0.0, 0.0, 1.0, frequency);
+        for _ in 0..octaves {
+            total += amplitude * self.at(x * frequency, y * frequency);
+            max += amplitude;
+            amplitude *= persistence;
+            frequency *= 2.0;
+        }
+        if max == 0.0 {
+            0.0
+        } else {
+            total / max
+        }
+    }
+}
+
+/// The dot product of (x, y) with one of 8 unit-ish gradients chosen by `hash`.
+fn gradient(hash: u8, x: f64, y: f64) -> f64 {
+    match hash & 7 {
+        0 => x + y,
+        1 => x - y,
+        2 => -x + y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+/// Perlin's quintic smoothstep, so the interpolation has no visible creases.
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + t * (b - a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_at_lattice_points() {
+        let noise = GradientNoise::new(1);
+        for (x, y) in [(0.0, 0.0), (3.0, 7.0), (-2.0, 300.0)] {
+            assert_eq!(noise.at(x, y), 0.0);
+        }
+    }
+
+    #[test]
+    fn varies_smoothly_and_stays_in_range() {
+        let noise = GradientNoise::new(2);
+        let mut previous = noise.at(0.0, 0.5);
+        for i in 1..1000 {
+            let value = noise.at(i as f64 * 0.01, 0.5);
+            assert!((-1.0..=1.0).contains(&value));
+            assert!((value - previous).abs() < 0.05);
+            previous = value;
+        }
+    }
+
+    #[test]
+    fn fbm_without_octaves_is_zero() {
+        assert_eq!(GradientNoise::new(3).fbm(1.5, 2.5, 0, 1.0, 0.5), 0.0);
+    }
+}
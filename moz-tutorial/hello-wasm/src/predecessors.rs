@@ -0,0 +1,198 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Coord, Rule, Universe, UniverseError};
+
+/// The most ticks `find_predecessors` searches back.
+pub(crate) const MAX_PREDECESSOR_DEPTH: u32 = 2;
+/// `find_predecessors` only searches universes smaller than this in each dimension.
+pub(crate) const MAX_PREDECESSOR_SIDE: usize = 16;
+/// The most predecessors `find_predecessors` returns, at each depth.
+pub(crate) const MAX_PREDECESSORS: usize = 64;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Universes of the same size, rule, and topology that become this one
+    /// after `depth` ticks. Empty if there are none; at depth 1, that makes this
+    /// universe a Garden of Eden.
+    ///
+    /// The search backtracks over every cell, so it is only supported for
+    /// Life-like rules, `depth` of at most 2, and universes smaller than 16x16.
+    /// Even then it can take a long time for dense patterns. At most 64
+    /// predecessors are found at each depth, so once that limit is reached,
+    /// depth 2 searches only the first 64 predecessors' predecessors.
+    /// Stencils are ignored.
+    pub fn find_predecessors(&self, depth: u32) -> Result<Vec<Universe>, UniverseError> {
+        let table = match self.rule {
+            Rule::Life { table } => table,
+            _ => {
+                return Err(UniverseError::UnsupportedSearch(format!(
+                    "predecessors need a Life-like rule, not {}",
+                    self.rule
+                )))
+            }
+        };
+        if depth > MAX_PREDECESSOR_DEPTH {
+            return Err(UniverseError::UnsupportedSearch(format!(
+                "predecessors at most {} ticks back, not {}",
+                MAX_PREDECESSOR_DEPTH, depth
+            )));
+        }
+        if self.width >= MAX_PREDECESSOR_SIDE || self.height >= MAX_PREDECESSOR_SIDE {
+            return Err(UniverseError::UnsupportedSearch(format!(
+                "predecessors of a {}x{} universe; the limit is {}x{}",
+                self.width,
+                self.height,
+                MAX_PREDECESSOR_SIDE - 1,
+                MAX_PREDECESSOR_SIDE - 1
+            )));
+        }
+        let search = PredecessorSearch::new(self, table);
+        let mut found: Vec<Vec<bool>> = vec![self.content.iter().map(|c| c.is_live()).collect()];
+        for _ in 0..depth {
+            let mut earlier = Vec::new();
+            for target in &found {
+                search.run(target, MAX_PREDECESSORS - earlier.len(), &mut earlier);
+                if earlier.len() == MAX_PREDECESSORS {
+                    break;
+                }
+            }
+            found = earlier;
+        }
+        Ok(found
+            .into_iter()
+            .map(|cells| {
+                let mut predecessor = self.blank_like(self.width, self.height);
+                for (cell, live) in predecessor.content.iter_mut().zip(cells) {
+                    *cell = if live { Cell::Alive } else { Cell::Dead };
+                }
+                predecessor.recount_live();
+                predecessor
+            })
+            .collect())
+    }
+}
+
+/// A backtracking search for the states that step into a target state. Cells
+/// are assigned in row-major order, and each cell's next state is checked as
+/// soon as it and all its neighbors are assigned.
+struct PredecessorSearch {
+    table: [u8; 18],
+    /// The indices of each cell's neighbors.
+    neighbors: Vec<Vec<usize>>,
+    /// `checks[i]`: the cells whose neighborhoods are complete once cell `i` is assigned.
+    checks: Vec<Vec<usize>>,
+}
+
+impl PredecessorSearch {
+    fn new(universe: &Universe, table: [u8; 18]) -> Self {
+        let (width, height) = (universe.width, universe.height);
+        let neighbors: Vec<Vec<usize>> = (0..width * height)
+            .map(|i| {
+                Coord {
+                    y: i / width,
+                    x: i % width,
+                }
+                .neighbors(width, height, universe.topology)
+                .map(|coord| coord.y * width + coord.x)
+                .collect()
+            })
+            .collect();
+        let mut checks = vec![Vec::new(); neighbors.len()];
+        for (i, around) in neighbors.iter().enumerate() {
+            let last = around.iter().copied().fold(i, usize::max);
+            checks[last].push(i);
+        }
+        PredecessorSearch {
+            table,
+            neighbors,
+            checks,
+        }
+    }
+
+    /// Append up to `limit` predecessors of `target` to `found`.
+    fn run(&self, target: &[bool], limit: usize, found: &mut Vec<Vec<bool>>) {
+        let mut state = vec![false; target.len()];
+        let goal = found.len() + limit;
+        self.extend(target, &mut state, 0, goal, found);
+    }
+
+    fn extend(
+        &self,
+        target: &[bool],
+        state: &mut Vec<bool>,
+        next: usize,
+        goal: usize,
+        found: &mut Vec<Vec<bool>>,
+    ) {
+        if next == state.len() {
+            found.push(state.clone());
+            return;
+        }
+        for live in [false, true] {
+            state[next] = live;
+            let consistent = self.checks[next].iter().all(|&i| {
+                let count = self.neighbors[i].iter().filter(|&&n| state[n]).count();
+                (self.table[count * 2 + state[i] as usize] == 1) == target[i]
+            });
+            if consistent {
+                self.extend(target, state, next + 1, goal, found);
+                if found.len() >= goal {
+                    return;
+                }
+            }
+        }
+        state[next] = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 5x5 universe holding a blinker in the given phase. It's small enough
+    /// that the blinker's other phase is among the first predecessors found.
+    fn blinker(vertical: bool) -> Universe {
+        let mut universe = Universe::new(5, 5);
+        let cells = if vertical {
+            vec![2, 1, 2, 2, 2, 3]
+        } else {
+            vec![1, 2, 2, 2, 3, 2]
+        };
+        universe.set_cells_bulk(cells, true);
+        universe
+    }
+
+    #[test]
+    fn blinker_phases_precede_each_other() {
+        for vertical in [false, true] {
+            let target = blinker(vertical);
+            let predecessors = target.find_predecessors(1).unwrap();
+            let other_phase = blinker(!vertical).live_coords();
+            assert!(predecessors.iter().any(|p| p.live_coords() == other_phase));
+            for mut predecessor in predecessors {
+                predecessor.tick();
+                assert_eq!(predecessor.live_coords(), target.live_coords());
+            }
+        }
+    }
+
+    #[test]
+    fn predecessors_two_ticks_back() {
+        let target = blinker(false);
+        let predecessors = target.find_predecessors(2).unwrap();
+        assert!(!predecessors.is_empty());
+        for mut predecessor in predecessors {
+            predecessor.tick_n(2);
+            assert_eq!(predecessor.live_coords(), target.live_coords());
+        }
+    }
+
+    #[test]
+    fn unsupported_searches_fail() {
+        assert!(blinker(false).find_predecessors(3).is_err());
+        assert!(Universe::new(16, 4).find_predecessors(1).is_err());
+        let mut wireworld = blinker(false);
+        wireworld.set_rule_wireworld();
+        assert!(wireworld.find_predecessors(1).is_err());
+    }
+}
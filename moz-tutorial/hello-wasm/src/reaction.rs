@@ -0,0 +1,133 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Coord, LogLevel, Op, TickStats, Universe};
+
+/// Diffusion rate of the U chemical.
+const DIFFUSION_U: f32 = 0.2100;
+/// Diffusion rate of the V chemical.
+const DIFFUSION_V: f32 = 0.1050;
+/// Rendered color (0xRRGGBB) where there is no V.
+const LOW_V_COLOR: u32 = 0x10_10_40;
+/// Rendered color (0xRRGGBB) at the highest V concentration shown.
+const HIGH_V_COLOR: u32 = 0xFF_E0_40;
+/// V concentrations at or above this are drawn in `HIGH_V_COLOR`.
+const MAX_SHOWN_V: f32 = 0.5;
+
+/// Parameters of the Gray-Scott reaction-diffusion model; see
+/// `Universe::set_rule_gray_scott`.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub(crate) struct GrayScott {
+    /// Feed rate of U.
+    f: f32,
+    /// Kill rate of V.
+    k: f32,
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Switch to a Gray-Scott reaction-diffusion simulation with feed rate `f`
+    /// and kill rate `k`. Instead of live and dead states, each cell holds
+    /// concentrations of two chemicals, U and V: U starts at 1 everywhere, and
+    /// the current live cells seed V. `tick` advances the reaction, leaving the
+    /// cells themselves alone, and `render2d` draws the V concentration.
+    /// Setting any other rule ends the simulation.
+    ///
+    /// Values of `f` around 0.01-0.06 and `k` around 0.045-0.065 give spots
+    /// (f = 0.035, k = 0.065), stripes (0.025, 0.06), or mazes (0.029, 0.057).
+    pub fn set_rule_gray_scott(&mut self, f: f64, k: f64) {
+        self.record(Op::SetRuleGrayScott { f, k });
+        self.gray_scott = Some(GrayScott {
+            f: f as f32,
+            k: k as f32,
+        });
+        self.seed_gray_scott();
+    }
+
+    /// The U and V concentrations of the cell at (x, y); both 0 unless the
+    /// Gray-Scott simulation is running.
+    pub fn get_concentrations(&self, x: usize, y: usize) -> Vec<f32> {
+        let i = (y % self.height) * self.width + x % self.width;
+        match self.content_f32.get(i) {
+            Some(&(u, v)) => vec![u, v],
+            None => vec![0.0, 0.0],
+        }
+    }
+}
+
+impl Universe {
+    /// Reset the concentrations for the current cells, if the Gray-Scott
+    /// simulation is running.
+    pub(crate) fn seed_gray_scott(&mut self) {
+        self.content_f32 = match self.gray_scott {
+            Some(_) => self
+                .content
+                .iter()
+                .map(|cell| {
+                    if cell.is_live() {
+                        (0.5, 0.25)
+                    } else {
+                        (1.0, 0.0)
+                    }
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+    }
+
+    /// Stop the Gray-Scott simulation, for a change of rule.
+    pub(crate) fn end_gray_scott(&mut self) {
+        self.gray_scott = None;
+        self.content_f32 = Vec::new();
+    }
+
+    /// Advance the Gray-Scott simulation one time step, if it is running; see
+    /// `step`. Returns None if it isn't.
+    pub(crate) fn step_gray_scott(&mut self) -> Option<TickStats> {
+        let GrayScott { f, k } = self.gray_scott?;
+        self.log(LogLevel::Debug, "advancing reaction-diffusion by one step");
+        let at = |x: isize, y: isize, here: Coord| {
+            // Edges with nothing beyond them reflect, so nothing flows out.
+            let there = self
+                .topology
+                .resolve(x, y, self.width, self.height)
+                .unwrap_or(here);
+            self.content_f32[there.y * self.width + there.x]
+        };
+        let mut next = Vec::with_capacity(self.content_f32.len());
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let here = Coord { y, x };
+                let (u, v) = self.content_f32[y * self.width + x];
+                let (xi, yi) = (x as isize, y as isize);
+                let around = [
+                    at(xi - 1, yi, here),
+                    at(xi + 1, yi, here),
+                    at(xi, yi - 1, here),
+                    at(xi, yi + 1, here),
+                ];
+                let laplacian_u = around.iter().map(|c| c.0).sum::<f32>() - 4.0 * u;
+                let laplacian_v = around.iter().map(|c| c.1).sum::<f32>() - 4.0 * v;
+                let reaction = u * v * v;
+                next.push((
+                    (u + DIFFUSION_U * laplacian_u - reaction + f * (1.0 - u)).clamp(0.0, 1.0),
+                    (v + DIFFUSION_V * laplacian_v + reaction - (f + k) * v).clamp(0.0, 1.0),
+                ));
+            }
+        }
+        self.content_f32 = next;
+        self.generation += 1;
+        Some(TickStats::default())
+    }
+
+    /// RGBA pixel data for the V concentrations; see `set_rule_gray_scott`.
+    pub(crate) fn gray_scott_pixels(&self) -> Vec<u8> {
+        self.pixels_with(|i, _| {
+            let t = (self.content_f32[i].1 / MAX_SHOWN_V).clamp(0.0, 1.0);
+            let channel = |shift: u32| {
+                let (low, high) = (LOW_V_COLOR >> shift & 0xFF, HIGH_V_COLOR >> shift & 0xFF);
+                (low as f32 + (high as f32 - low as f32) * t).round() as u32
+            };
+            channel(16) << 24 | channel(8) << 16 | channel(0) << 8 | 0xFF
+        })
+    }
+}
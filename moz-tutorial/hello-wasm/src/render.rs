@@ -0,0 +1,479 @@
+use wasm_bindgen::prelude::*;
+use web_sys::{CanvasRenderingContext2d, ImageData};
+
+use crate::{checked_cells, now_ms, Coord, Topology, Universe};
+
+/// Outline color for live cells in the neighbor overlay.
+const LIVE_BORDER_COLOR: &str = "#ffffff";
+/// Font of the `render2d_with_stats_overlay` text.
+const STATS_FONT: &str = "12px monospace";
+/// Color of the `render2d_with_stats_overlay` text.
+const STATS_COLOR: &str = "#ffffff";
+/// Distance, in pixels, of the stats text from the canvas's bottom-left corner.
+const STATS_MARGIN: f64 = 4.0;
+/// `RenderConfig`'s grid color unless set otherwise.
+const DEFAULT_GRID_COLOR: &str = "#808080";
+
+/// Drawing settings for the renderers that don't take them as arguments;
+/// see `Universe::set_render_config`.
+#[wasm_bindgen]
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct RenderConfig {
+    grid_color: String,
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        RenderConfig {
+            grid_color: DEFAULT_GRID_COLOR.to_owned(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl RenderConfig {
+    pub fn new() -> RenderConfig {
+        RenderConfig::default()
+    }
+
+    /// The CSS color of grid and seam lines.
+    pub fn grid_color(&self) -> String {
+        self.grid_color.clone()
+    }
+    pub fn set_grid_color(&mut self, color: &str) {
+        self.grid_color = color.to_owned();
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Render each cell shaded by its live neighbor count, from very dark (0)
+    /// to very bright (8), with live cells outlined in white.
+    /// Cells are scaled up to fill the canvas. Intended for inspecting rules,
+    /// not for regular display.
+    pub fn render2d_neighbor_overlay(
+        &self,
+        canvas: &CanvasRenderingContext2d,
+    ) -> Result<(), JsValue> {
+        let size = self.cell_size_for(canvas);
+        canvas.set_stroke_style_str(LIVE_BORDER_COLOR);
+        for (coord, cell) in self {
+            let level = 16 + self.live_neighbors(coord) * 29;
+            let color = format!("rgb({0}, {0}, {0})", level);
+            let (x, y) = (coord.x as f64 * size, coord.y as f64 * size);
+            canvas.set_fill_style_str(&color);
+            canvas.fill_rect(x, y, size, size);
+            if cell.is_live() {
+                canvas.stroke_rect(x + 0.5, y + 0.5, size - 1.0, size - 1.0);
+            }
+        }
+        Ok(())
+    }
+
+    /// Render each cell as a `scale`-pixel square, and outline in
+    /// `highlight_color` the edge cells whose neighborhood reaches across to
+    /// the opposite edge: those with a different live neighbor count than they
+    /// would have if cells beyond the edges were dead.
+    pub fn render2d_with_wrap_highlight(
+        &self,
+        canvas: &CanvasRenderingContext2d,
+        scale: f64,
+        highlight_color: &str,
+    ) -> Result<(), JsValue> {
+        for (coord, cell) in self {
+            canvas.set_fill_style_str(&cell.css_color());
+            canvas.fill_rect(coord.x as f64 * scale, coord.y as f64 * scale, scale, scale);
+        }
+        canvas.set_stroke_style_str(highlight_color);
+        for coord in self.edge_coords() {
+            if self.live_neighbors(coord) != self.live_neighbors_under(coord, Topology::Bounded) {
+                canvas.stroke_rect(
+                    coord.x as f64 * scale + 0.5,
+                    coord.y as f64 * scale + 0.5,
+                    scale - 1.0,
+                    scale - 1.0,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Render each cell as a rectangle, stretched so the universe fills the
+    /// canvas exactly even when it isn't a whole number of pixels per cell.
+    /// Rectangles are rounded up to whole pixels, so neighbors overlap
+    /// rather than leaving gaps.
+    pub fn render2d_scaled(&self, canvas: &CanvasRenderingContext2d) -> Result<(), JsValue> {
+        let element = canvas
+            .canvas()
+            .ok_or_else(|| js_sys::Error::new("context has no canvas"))?;
+        let scale_x = element.width() as f64 / self.width as f64;
+        let scale_y = element.height() as f64 / self.height as f64;
+        for (coord, cell) in self {
+            canvas.set_fill_style_str(&cell.css_color());
+            canvas.fill_rect(
+                coord.x as f64 * scale_x,
+                coord.y as f64 * scale_y,
+                scale_x.ceil(),
+                scale_y.ceil(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Draw only the lines between cells, for overlaying on a custom renderer.
+    /// Cells are `cell_size_px` square, with the grid's top-left corner at
+    /// (offset_x, offset_y). Draws nothing if cells are under 2px, where the
+    /// grid would hide them.
+    pub fn render2d_grid_only(
+        &self,
+        canvas: &CanvasRenderingContext2d,
+        cell_size_px: f64,
+        color: &str,
+        offset_x: f64,
+        offset_y: f64,
+    ) -> Result<(), JsValue> {
+        if cell_size_px < 2.0 {
+            return Ok(());
+        }
+        let right = offset_x + self.width as f64 * cell_size_px;
+        let bottom = offset_y + self.height as f64 * cell_size_px;
+        canvas.begin_path();
+        for column in 0..=self.width {
+            let x = offset_x + column as f64 * cell_size_px;
+            canvas.move_to(x, offset_y);
+            canvas.line_to(x, bottom);
+        }
+        for row in 0..=self.height {
+            let y = offset_y + row as f64 * cell_size_px;
+            canvas.move_to(offset_x, y);
+            canvas.line_to(right, y);
+        }
+        canvas.set_stroke_style_str(color);
+        canvas.stroke();
+        Ok(())
+    }
+
+    /// Render the universe with each cell's color from
+    /// `color_fn(x, y, is_alive, age, live_neighbor_count)`, which returns the
+    /// color as a `0xRRGGBBAA` number; `age` is 0 unless age tracking is on.
+    /// Fails if `color_fn` throws or returns something other than a number.
+    ///
+    /// This crosses from Wasm to JS once per cell, 262,144 times for a 512x512
+    /// universe, so it suits small universes and special effects.
+    pub fn render2d_custom(
+        &self,
+        canvas: &CanvasRenderingContext2d,
+        color_fn: &js_sys::Function,
+    ) -> Result<(), JsValue> {
+        let mut pixels = Vec::with_capacity(self.content.len() * 4);
+        for (here, cell) in self {
+            let args = js_sys::Array::of5(
+                &JsValue::from(here.x as u32),
+                &JsValue::from(here.y as u32),
+                &JsValue::from_bool(cell.is_live()),
+                &JsValue::from(self.get_age(here.x, here.y)),
+                &JsValue::from(self.live_neighbors(here) as u8),
+            );
+            let color = color_fn
+                .apply(&JsValue::NULL, &args)?
+                .as_f64()
+                .ok_or_else(|| js_sys::Error::new("color_fn must return a number"))?;
+            pixels.extend_from_slice(&(color as u32).to_be_bytes());
+        }
+        self.put_pixels(canvas, &pixels)
+    }
+
+    /// Draw `text` on the canvas with its baseline starting at (x, y), in a CSS
+    /// `font` and `color`, over whatever is already drawn.
+    pub fn render2d_text_overlay(
+        &self,
+        canvas: &CanvasRenderingContext2d,
+        text: &str,
+        x: f64,
+        y: f64,
+        font: &str,
+        color: &str,
+    ) -> Result<(), JsValue> {
+        canvas.save();
+        canvas.set_font(font);
+        canvas.set_fill_style_str(color);
+        let drawn = canvas.fill_text(text, x, y);
+        canvas.restore();
+        drawn
+    }
+
+    /// Render the universe like `render2d`, then stamp "Gen: 42 | Pop: 137 |
+    /// TPS: 60.0" in the bottom-left corner. TPS is the ticks per second since
+    /// the previous call, and 0 on the first.
+    pub fn render2d_with_stats_overlay(
+        &mut self,
+        canvas: &CanvasRenderingContext2d,
+    ) -> Result<(), JsValue> {
+        self.render2d(canvas)?;
+        let now = now_ms();
+        let tps = match self.overlay_sample {
+            Some((then, generation)) if now > then => {
+                self.generation.saturating_sub(generation) as f64 * 1000.0 / (now - then)
+            }
+            _ => 0.0,
+        };
+        self.overlay_sample = Some((now, self.generation));
+        let text = format!(
+            "Gen: {} | Pop: {} | TPS: {:.1}",
+            self.generation, self.live_count, tps
+        );
+        let bottom = canvas
+            .canvas()
+            .map_or(0.0, |element| element.height() as f64);
+        self.render2d_text_overlay(
+            canvas,
+            &text,
+            STATS_MARGIN,
+            bottom - STATS_MARGIN,
+            STATS_FONT,
+            STATS_COLOR,
+        )
+    }
+
+    /// Render the whole universe into `mini_canvas` at one pixel per cell, and
+    /// outline in `highlight_color` the part that `main_canvas` shows: the
+    /// cells from (viewport_x, viewport_y), at `scale` pixels per cell. Where
+    /// the viewport wraps around an edge, its outline does too, in pieces.
+    pub fn render2d_minimap(
+        &self,
+        main_canvas: &CanvasRenderingContext2d,
+        mini_canvas: &CanvasRenderingContext2d,
+        viewport_x: f64,
+        viewport_y: f64,
+        scale: f64,
+        highlight_color: &str,
+    ) -> Result<(), JsValue> {
+        self.put_pixels(mini_canvas, &self.display_pixels())?;
+        let (width, height) = (self.width as f64, self.height as f64);
+        let (view_w, view_h) = main_canvas.canvas().map_or((0.0, 0.0), |element| {
+            (
+                (element.width() as f64 / scale).min(width),
+                (element.height() as f64 / scale).min(height),
+            )
+        });
+        let (x, y) = (viewport_x.rem_euclid(width), viewport_y.rem_euclid(height));
+        mini_canvas.save();
+        mini_canvas.begin_path();
+        mini_canvas.rect(0.0, 0.0, width, height);
+        mini_canvas.clip();
+        mini_canvas.set_stroke_style_str(highlight_color);
+        mini_canvas.set_line_width(1.0);
+        // Each piece is drawn at its offset and one universe-size back, so
+        // the parts that run past the right or bottom edge reappear on the left
+        // or top; clipping to the universe hides the rest.
+        for dx in [0.0, -width] {
+            for dy in [0.0, -height] {
+                if (dx < 0.0 && x + view_w <= width) || (dy < 0.0 && y + view_h <= height) {
+                    continue;
+                }
+                mini_canvas.stroke_rect(x + dx + 0.5, y + dy + 0.5, view_w - 1.0, view_h - 1.0);
+            }
+        }
+        mini_canvas.restore();
+        Ok(())
+    }
+
+    /// Use `config` for later rendering.
+    pub fn set_render_config(&mut self, config: &RenderConfig) {
+        self.render_config = config.clone();
+    }
+
+    pub fn render_config(&self) -> RenderConfig {
+        self.render_config.clone()
+    }
+
+    /// Set whether `render2d_viewport` and `render2d_tiled` draw a line, in
+    /// the `RenderConfig` grid color, along the universe's edges. On a torus
+    /// the edges are otherwise invisible, so a pattern crossing one seems to
+    /// jump from one side of the view to the other.
+    pub fn set_seam_visualization(&mut self, enabled: bool) {
+        self.seam_visualization = enabled;
+    }
+
+    /// Render the part of the universe that fits in `canvas` at `scale`
+    /// pixels per cell, with the point (viewport_x, viewport_y) at the
+    /// top-left corner and the view wrapping around the universe's edges:
+    /// the view `render2d_minimap` outlines. The view is at most one universe
+    /// wide and tall. Does nothing unless `scale` is positive.
+    pub fn render2d_viewport(
+        &self,
+        canvas: &CanvasRenderingContext2d,
+        viewport_x: f64,
+        viewport_y: f64,
+        scale: f64,
+    ) -> Result<(), JsValue> {
+        let element = match canvas.canvas() {
+            Some(element) if scale > 0.0 => element,
+            _ => return Ok(()),
+        };
+        let (width, height) = (self.width as f64, self.height as f64);
+        let view_w = (element.width() as f64 / scale).min(width) * scale;
+        let view_h = (element.height() as f64 / scale).min(height) * scale;
+        let (x, y) = (viewport_x.rem_euclid(width), viewport_y.rem_euclid(height));
+        let (first_x, first_y) = (x.floor() as usize, y.floor() as usize);
+        let (shift_x, shift_y) = ((x - x.floor()) * scale, (y - y.floor()) * scale);
+        let columns = ((view_w + shift_x) / scale).ceil() as usize;
+        let rows = ((view_h + shift_y) / scale).ceil() as usize;
+        let pixels = self.display_pixels();
+        let color_at = |column: usize, row: usize| {
+            let index =
+                ((first_y + row) % self.height) * self.width + (first_x + column) % self.width;
+            let rgba = &pixels[index * 4..index * 4 + 4];
+            u32::from_be_bytes([rgba[0], rgba[1], rgba[2], rgba[3]])
+        };
+
+        canvas.save();
+        canvas.begin_path();
+        canvas.rect(0.0, 0.0, view_w, view_h);
+        canvas.clip();
+        for row in 0..rows {
+            let top = row as f64 * scale - shift_y;
+            // Runs of same-colored cells are filled together.
+            let mut start = 0;
+            for column in 1..=columns {
+                let color = color_at(start, row);
+                if column < columns && color_at(column, row) == color {
+                    continue;
+                }
+                canvas.set_fill_style_str(&format!("#{:08x}", color));
+                canvas.fill_rect(
+                    start as f64 * scale - shift_x,
+                    top,
+                    (column - start) as f64 * scale,
+                    scale,
+                );
+                start = column;
+            }
+        }
+        if self.seam_visualization {
+            canvas.begin_path();
+            for column in (0..=columns).filter(|column| (first_x + column) % self.width == 0) {
+                let left = column as f64 * scale - shift_x;
+                canvas.move_to(left, 0.0);
+                canvas.line_to(left, view_h);
+            }
+            for row in (0..=rows).filter(|row| (first_y + row) % self.height == 0) {
+                let top = row as f64 * scale - shift_y;
+                canvas.move_to(0.0, top);
+                canvas.line_to(view_w, top);
+            }
+            self.stroke_seams(canvas);
+        }
+        canvas.restore();
+        Ok(())
+    }
+
+    /// Render the universe `tile_nx` by `tile_ny` times side by side, at
+    /// `scale` pixels per cell, so that a pattern crossing a wrapped edge can
+    /// be seen to carry on into the neighboring copy instead of jumping to the
+    /// far side of the canvas. Counts and `scale` below 1 are treated as 1.
+    /// Fails if the image would have more pixels than a universe can have
+    /// cells.
+    pub fn render2d_tiled(
+        &self,
+        canvas: &CanvasRenderingContext2d,
+        tile_nx: u32,
+        tile_ny: u32,
+        scale: u32,
+    ) -> Result<(), JsValue> {
+        let (tile_nx, tile_ny) = (tile_nx.max(1) as usize, tile_ny.max(1) as usize);
+        let scale = scale.max(1) as usize;
+        let (tile_w, tile_h) = (
+            self.width.saturating_mul(scale),
+            self.height.saturating_mul(scale),
+        );
+        let (full_w, full_h) = (
+            tile_w.saturating_mul(tile_nx),
+            tile_h.saturating_mul(tile_ny),
+        );
+        let mut pixels = Vec::with_capacity(checked_cells(full_w, full_h)? * 4);
+        for row in self.display_pixels().chunks_exact(self.width * 4) {
+            let mut line = Vec::with_capacity(full_w * 4);
+            for _ in 0..tile_nx {
+                for rgba in row.chunks_exact(4) {
+                    for _ in 0..scale {
+                        line.extend_from_slice(rgba);
+                    }
+                }
+            }
+            for _ in 0..scale {
+                pixels.extend_from_slice(&line);
+            }
+        }
+        let band = pixels.len();
+        for _ in 1..tile_ny {
+            pixels.extend_from_within(..band);
+        }
+        let data = ImageData::new_with_u8_clamped_array_and_sh(
+            wasm_bindgen::Clamped(&pixels),
+            full_w as u32,
+            full_h as u32,
+        )?;
+        canvas.put_image_data(&data, 0.0, 0.0)?;
+        if self.seam_visualization {
+            canvas.begin_path();
+            for i in 1..tile_nx {
+                let x = (i * tile_w) as f64 - 0.5;
+                canvas.move_to(x, 0.0);
+                canvas.line_to(x, full_h as f64);
+            }
+            for j in 1..tile_ny {
+                let y = (j * tile_h) as f64 - 0.5;
+                canvas.move_to(0.0, y);
+                canvas.line_to(full_w as f64, y);
+            }
+            self.stroke_seams(canvas);
+        }
+        Ok(())
+    }
+
+    /// See `Universe::render_to_ppm`.
+    #[wasm_bindgen(js_name = render_to_ppm)]
+    pub fn render_to_ppm_js(&self) -> js_sys::Uint8Array {
+        js_sys::Uint8Array::from(&self.render_to_ppm()[..])
+    }
+}
+
+impl Universe {
+    /// Stroke the current path as seam lines; see `set_seam_visualization`.
+    fn stroke_seams(&self, canvas: &CanvasRenderingContext2d) {
+        canvas.set_stroke_style_str(&self.render_config.grid_color);
+        canvas.set_line_width(1.0);
+        canvas.stroke();
+    }
+
+    /// A binary (P6) PPM image of the universe, one pixel per cell in the same
+    /// colors as `render2d`, without needing a canvas.
+    pub fn render_to_ppm(&self) -> Vec<u8> {
+        let mut ppm = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        for rgba in self.display_pixels().chunks_exact(4) {
+            ppm.extend_from_slice(&rgba[..3]);
+        }
+        ppm
+    }
+
+    /// The cells on the edges of the universe, each once.
+    fn edge_coords(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.cells().map(|(coord, _)| coord).filter(move |coord| {
+            coord.x == 0 || coord.y == 0 || coord.x == self.width - 1 || coord.y == self.height - 1
+        })
+    }
+
+    /// The largest square cell size, in pixels, at which the whole universe fits
+    /// on the canvas; at least 1.
+    pub(crate) fn cell_size_for(&self, canvas: &CanvasRenderingContext2d) -> f64 {
+        canvas
+            .canvas()
+            .map(|element| {
+                let x = element.width() as f64 / self.width as f64;
+                let y = element.height() as f64 / self.height as f64;
+                x.min(y).floor().max(1.0)
+            })
+            .unwrap_or(1.0)
+    }
+}
@@ -0,0 +1,326 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, ParseError, SuperimposeMode, Universe, UniverseError};
+
+/// A recorded operation on a Universe.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub enum Op {
+    Tick,
+    TickN(usize),
+    SetCell {
+        x: usize,
+        y: usize,
+        alive: bool,
+    },
+    /// `coords` is a flat list of (x, y) pairs.
+    SetCells {
+        coords: Vec<u32>,
+        alive: bool,
+    },
+    Randomize {
+        seed: u64,
+    },
+    RandomizeWithDensity {
+        seed: u64,
+        density: f64,
+    },
+    RandomizePerlin {
+        seed: u64,
+        octaves: u32,
+        frequency: f64,
+        persistence: f64,
+        threshold: f64,
+    },
+    Perturb {
+        cx: usize,
+        cy: usize,
+        radius: usize,
+        density: f64,
+        seed: u64,
+    },
+    Resize {
+        w: usize,
+        h: usize,
+        anchor: String,
+    },
+    SetRule(String),
+    SetRuleGrayScott {
+        f: f64,
+        k: f64,
+    },
+    SetTopology(String),
+    SetStencil(Vec<u8>),
+    ClearStencil,
+    TickWeightedRule {
+        birth_threshold: f64,
+        survival_min: f64,
+        survival_max: f64,
+    },
+    ApplyBlurFilter(u32),
+    ApplyRuleToRegion {
+        rule: String,
+        x: usize,
+        y: usize,
+        w: usize,
+        h: usize,
+    },
+    DrawWire {
+        x1: usize,
+        y1: usize,
+        x2: usize,
+        y2: usize,
+    },
+    PlaceElectron {
+        head_x: usize,
+        head_y: usize,
+        tail_x: usize,
+        tail_y: usize,
+    },
+    RotateCw,
+    FlipHorizontal,
+    CropToBoundingBox,
+    CenterOnLiveCells,
+    /// `cells` is the overlaid pattern, `width` cells wide, in row-major order.
+    Superimpose {
+        width: usize,
+        cells: Vec<Cell>,
+        dx: i32,
+        dy: i32,
+        mode: SuperimposeMode,
+    },
+    /// `cells` is the tiled pattern, `width` cells wide, in row-major order.
+    TileInto {
+        width: usize,
+        cells: Vec<Cell>,
+        offset_x: usize,
+        offset_y: usize,
+        nx: usize,
+        ny: usize,
+    },
+    /// Kill the cells of the selection, which was the `w` x `h` region at (x, y).
+    DeleteSelection {
+        x: usize,
+        y: usize,
+        w: usize,
+        h: usize,
+    },
+    SetUndoCapacity(usize),
+    Undo,
+    EnableStepBack,
+    DisableStepBack,
+    StepBack,
+    SetTrackAge(bool),
+    SetAutoExpand {
+        margin: usize,
+        max_width: usize,
+        max_height: usize,
+    },
+    DisableAutoExpand,
+    /// The bytes `sync_from_shared` loaded, one per cell.
+    SyncFromShared(Vec<u8>),
+}
+
+/// A log of operations applied to a Universe, which can be replayed
+/// to reproduce its state exactly.
+///
+/// Replay starts from a copy of the universe as it was when the log was
+/// attached, including settings such as age tracking and undo capacity.
+/// Every operation that changes the cells or how later ticks behave is
+/// recorded, with one exception: `tick_with_rule_fn`, whose rule is a JS
+/// function that a log can't hold. Rendering and analysis aren't recorded,
+/// nor are settings that only affect them, such as heat map tracking and
+/// the trail buffer.
+#[wasm_bindgen]
+#[derive(PartialEq, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ReplayLog {
+    ops: Vec<Op>,
+}
+
+impl ReplayLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ops(&self) -> &[Op] {
+        &self.ops
+    }
+
+    pub fn push(&mut self, op: Op) {
+        self.ops.push(op)
+    }
+
+    /// Apply each operation in the log to `universe`, in order.
+    pub fn apply(&self, mut universe: Universe) -> Result<Universe, ParseError> {
+        for op in self.ops.iter() {
+            match op.clone() {
+                Op::Tick => universe.tick(),
+                Op::TickN(n) => universe.tick_n(n),
+                Op::SetCell { x, y, alive } => universe.set_cell(x, y, alive),
+                Op::SetCells { coords, alive } => universe.set_cells_bulk(coords, alive),
+                Op::Randomize { seed } => universe.randomize(seed),
+                Op::RandomizeWithDensity { seed, density } => {
+                    universe.randomize_with_density(seed, density)
+                }
+                Op::RandomizePerlin {
+                    seed,
+                    octaves,
+                    frequency,
+                    persistence,
+                    threshold,
+                } => universe.randomize_perlin(seed, octaves, frequency, persistence, threshold),
+                Op::Perturb {
+                    cx,
+                    cy,
+                    radius,
+                    density,
+                    seed,
+                } => universe.perturb(cx, cy, radius, density, seed),
+                Op::Resize { w, h, anchor } => universe.resize(w, h, &anchor)?,
+                Op::SetRule(rule) => universe.set_rule(&rule)?,
+                Op::SetRuleGrayScott { f, k } => universe.set_rule_gray_scott(f, k),
+                Op::SetTopology(topology) => universe.set_topology(&topology)?,
+                Op::SetStencil(stencil) => universe.set_stencil(stencil)?,
+                Op::ClearStencil => universe.clear_stencil(),
+                Op::TickWeightedRule {
+                    birth_threshold,
+                    survival_min,
+                    survival_max,
+                } => universe.tick_weighted_rule(birth_threshold, survival_min, survival_max)?,
+                Op::ApplyBlurFilter(radius) => universe.apply_blur_filter(radius),
+                Op::ApplyRuleToRegion { rule, x, y, w, h } => {
+                    universe.apply_rule_to_region(&rule, x, y, w, h)?
+                }
+                Op::DrawWire { x1, y1, x2, y2 } => universe.draw_wire(x1, y1, x2, y2),
+                Op::PlaceElectron {
+                    head_x,
+                    head_y,
+                    tail_x,
+                    tail_y,
+                } => universe.place_electron(head_x, head_y, tail_x, tail_y),
+                Op::RotateCw => universe.rotate_cw()?,
+                Op::FlipHorizontal => universe.flip_horizontal()?,
+                Op::CropToBoundingBox => universe.crop_to_bounding_box(),
+                Op::CenterOnLiveCells => {
+                    universe.center_on_live_cells();
+                }
+                Op::Superimpose {
+                    width,
+                    cells,
+                    dx,
+                    dy,
+                    mode,
+                } => universe.superimpose(&Universe::from_cells(width, cells)?, dx, dy, mode)?,
+                Op::TileInto {
+                    width,
+                    cells,
+                    offset_x,
+                    offset_y,
+                    nx,
+                    ny,
+                } => Universe::from_cells(width, cells)?.tile_into(
+                    &mut universe,
+                    offset_x,
+                    offset_y,
+                    nx,
+                    ny,
+                ),
+                Op::DeleteSelection { x, y, w, h } => {
+                    universe.select(x, y, w, h);
+                    universe.delete_selection();
+                }
+                Op::SetUndoCapacity(capacity) => universe.set_undo_capacity(capacity),
+                Op::Undo => {
+                    universe.undo();
+                }
+                Op::EnableStepBack => universe.enable_step_back(),
+                Op::DisableStepBack => universe.disable_step_back(),
+                Op::StepBack => {
+                    universe.step_back();
+                }
+                Op::SetTrackAge(enabled) => universe.set_track_age(enabled),
+                Op::SetAutoExpand {
+                    margin,
+                    max_width,
+                    max_height,
+                } => universe.set_auto_expand(margin, max_width, max_height),
+                Op::DisableAutoExpand => universe.disable_auto_expand(),
+                Op::SyncFromShared(bytes) => universe.load_live_bytes(&bytes)?,
+            }
+        }
+        Ok(universe)
+    }
+
+    /// Replay a JSON-encoded log onto `initial`.
+    pub fn replay(initial: Universe, json: &str) -> Result<Universe, ParseError> {
+        ReplayLog::from_json(json)?.apply(initial)
+    }
+}
+
+impl Universe {
+    /// The live cells, as a flat list of (x, y) pairs for an `Op`.
+    pub(crate) fn live_coords(&self) -> Vec<u32> {
+        self.cells()
+            .filter(|(_, cell)| cell.is_live())
+            .flat_map(|(coord, _)| [coord.x as u32, coord.y as u32])
+            .collect()
+    }
+
+    /// A universe `width` cells wide holding `cells`, in row-major order.
+    fn from_cells(width: usize, cells: Vec<Cell>) -> Result<Universe, UniverseError> {
+        let mut universe = Universe::try_new(width, cells.len() / width.max(1))?;
+        if universe.content.len() != cells.len() {
+            return Err(UniverseError::InvalidPattern(format!(
+                "{} cells do not fill rows of {}",
+                cells.len(),
+                width
+            )));
+        }
+        universe.content = cells;
+        universe.recount_live();
+        Ok(universe)
+    }
+}
+
+#[wasm_bindgen]
+impl ReplayLog {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("replay log is always serializable")
+    }
+
+    pub fn from_json(json: &str) -> Result<ReplayLog, ParseError> {
+        serde_json::from_str(json).map_err(|err| ParseError::InvalidJson(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[test]
+    fn replays_superimpose_of_multi_state_source() {
+        // Firing, refractory and dead cells.
+        let mut source = Universe::from_pattern(&["....", ".##.", "...."]).unwrap();
+        source.set_rule_brians_brain();
+        source.tick();
+        let log = Arc::new(Mutex::new(ReplayLog::new()));
+        let mut universe = Universe::new(8, 6);
+        universe.randomize(5);
+        universe.attach_log(log.clone());
+        universe
+            .superimpose(&source, 1, 2, SuperimposeMode::Replace)
+            .unwrap();
+        universe
+            .superimpose(&source, 4, 3, SuperimposeMode::And)
+            .unwrap();
+        universe.tick();
+
+        let mut initial = Universe::new(8, 6);
+        initial.randomize(5);
+        let json = log.lock().unwrap().to_json();
+        let replayed = ReplayLog::replay(initial, &json).unwrap();
+        assert_eq!(replayed.content, universe.content);
+    }
+}
@@ -0,0 +1,299 @@
+use wasm_bindgen::prelude::*;
+
+use crate::rule::Rule;
+use crate::{Cell, Coord, ParseError, Universe};
+
+/// Maximum line length of encoded RLE, per the format's convention.
+const RLE_LINE_LENGTH: usize = 70;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Parse a pattern in Run Length Encoded format.
+    /// The universe takes the dimensions and rule from the header line, and
+    /// its `position` from a `#P x y` (or `#R x y`) line or a CXRLE `Pos=x,y`.
+    pub fn from_rle(rle: &str) -> Result<Universe, ParseError> {
+        let invalid = |msg: &str| ParseError::InvalidRle(msg.to_owned());
+
+        let mut header = None;
+        let mut body = String::new();
+        let mut position = None;
+        for line in rle.lines().map(str::trim) {
+            if let Some(directive) = line.strip_prefix("#P").or_else(|| line.strip_prefix("#R")) {
+                position = Some(parse_position(directive.split_whitespace())?);
+                continue;
+            }
+            if let Some(fields) = line.strip_prefix("#CXRLE") {
+                let pos = fields
+                    .split_whitespace()
+                    .find_map(|field| field.strip_prefix("Pos="));
+                if let Some(pos) = pos {
+                    position = Some(parse_position(pos.split(','))?);
+                }
+                continue;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if header.is_none() && line.starts_with('x') {
+                header = Some(line);
+            } else {
+                body.push_str(line);
+            }
+        }
+
+        let (mut width, mut height, mut rule) = (None, None, Rule::default());
+        for field in header
+            .ok_or_else(|| invalid("missing header line"))?
+            .split(',')
+        {
+            let (key, value) = field
+                .split_once('=')
+                .ok_or_else(|| invalid("malformed header"))?;
+            let value = value.trim();
+            match key.trim() {
+                "x" => width = Some(value.parse().map_err(|_| invalid("bad width"))?),
+                "y" => height = Some(value.parse().map_err(|_| invalid("bad height"))?),
+                "rule" => rule = Rule::parse(value)?,
+                _ => (),
+            }
+        }
+        let (width, height): (usize, usize) = match (width, height) {
+            (Some(w), Some(h)) => (w, h),
+            _ => return Err(invalid("header must give x and y")),
+        };
+
+        let mut universe = Universe::try_new(width.max(1), height.max(1))?;
+        universe.rule = rule;
+        universe.position = position;
+        let (mut x, mut y) = (0, 0);
+        let mut run: Option<usize> = None;
+        for c in body.chars() {
+            if let Some(digit) = c.to_digit(10) {
+                run = Some(run.unwrap_or(0) * 10 + digit as usize);
+                continue;
+            }
+            let count = run.take().unwrap_or(1);
+            match c {
+                '!' => break,
+                '$' => {
+                    y += count;
+                    x = 0;
+                }
+                'b' | '.' => x += count,
+                c if c.is_ascii_alphabetic() => {
+                    if x + count > width || y >= height {
+                        return Err(invalid("pattern exceeds header dimensions"));
+                    }
+                    for _ in 0..count {
+                        universe.put(Coord { y, x }, Cell::Alive);
+                        x += 1;
+                    }
+                }
+                c if c.is_whitespace() => (),
+                _ => return Err(invalid("unexpected character")),
+            }
+        }
+        Ok(universe)
+    }
+
+    /// Encode the universe in Run Length Encoded format.
+    pub fn to_rle(&self) -> String {
+        let mut tokens = Vec::new();
+        let mut pending_rows = 0;
+        for row in self.content.chunks(self.width) {
+            let mut runs: Vec<(bool, usize)> = Vec::new();
+            for cell in row {
+                match runs.last_mut() {
+                    Some((live, n)) if *live == cell.is_live() => *n += 1,
+                    _ => runs.push((cell.is_live(), 1)),
+                }
+            }
+            if let Some((false, _)) = runs.last() {
+                runs.pop();
+            }
+
+            if !runs.is_empty() {
+                if pending_rows > 0 {
+                    tokens.push(run_token(pending_rows, '$'));
+                }
+                pending_rows = 0;
+                for (live, n) in runs {
+                    tokens.push(run_token(n, if live { 'o' } else { 'b' }));
+                }
+            }
+            pending_rows += 1;
+        }
+        tokens.push("!".to_owned());
+
+        let mut rle = format!(
+            "x = {}, y = {}, rule = {}\n",
+            self.width, self.height, self.rule
+        );
+        let mut line = String::new();
+        for token in tokens {
+            if line.len() + token.len() > RLE_LINE_LENGTH {
+                rle.push_str(&line);
+                rle.push('\n');
+                line.clear();
+            }
+            line.push_str(&token);
+        }
+        rle.push_str(&line);
+        rle.push('\n');
+        rle
+    }
+
+    /// Encode the universe in RLE like `to_rle`, placed at (offset_x, offset_y)
+    /// in a larger scene: both as a `#CXRLE Pos=x,y` line, for Golly, and as a
+    /// `#P x y` line, for other readers.
+    pub fn to_life_rle_with_position(&self, offset_x: i64, offset_y: i64) -> String {
+        format!(
+            "#CXRLE Pos={},{}\n#P {} {}\n{}",
+            offset_x,
+            offset_y,
+            offset_x,
+            offset_y,
+            self.to_rle()
+        )
+    }
+
+    /// The x coordinate of the pattern's top-left corner in a larger scene, if
+    /// it was read from RLE with a position; see `from_rle`.
+    pub fn position_x(&self) -> Option<i64> {
+        self.position.map(|(x, _)| x)
+    }
+
+    /// The y coordinate of the pattern's top-left corner; see `position_x`.
+    pub fn position_y(&self) -> Option<i64> {
+        self.position.map(|(_, y)| y)
+    }
+
+    /// Encode the live cells' bounding box in RLE, with the comment header
+    /// LifeWiki expects: `#N name`, `#O author`, the description in `#C` lines
+    /// wrapped at 70 characters, and `#P x y`, the box's position in this
+    /// universe. Empty fields are left out, and an all-dead universe encodes
+    /// as a single dead cell.
+    pub fn encode_as_rle_with_comments(
+        &self,
+        author: &str,
+        description: &str,
+        name: &str,
+    ) -> String {
+        let mut header = String::new();
+        if !name.is_empty() {
+            header.push_str(&format!("#N {}\n", name));
+        }
+        if !author.is_empty() {
+            header.push_str(&format!("#O {}\n", author));
+        }
+        for line in wrap_words(description, RLE_LINE_LENGTH - "#C ".len()) {
+            header.push_str(&format!("#C {}\n", line));
+        }
+        let (x, y, width, height) = self.bounding_box().unwrap_or((0, 0, 1, 1));
+        header.push_str(&format!("#P {} {}\n", x, y));
+        header + &self.subgrid(x, y, width, height).to_rle()
+    }
+}
+
+/// `text` split into lines of at most `width` characters, breaking between
+/// words; words longer than `width` get lines of their own.
+fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// An x, y pair from a position directive.
+fn parse_position<'a>(mut fields: impl Iterator<Item = &'a str>) -> Result<(i64, i64), ParseError> {
+    let invalid = || ParseError::InvalidRle("malformed position".to_owned());
+    let mut coordinate = || -> Result<i64, ParseError> {
+        fields
+            .next()
+            .and_then(|field| field.trim().parse().ok())
+            .ok_or_else(invalid)
+    };
+    Ok((coordinate()?, coordinate()?))
+}
+
+fn run_token(count: usize, tag: char) -> String {
+    if count == 1 {
+        tag.to_string()
+    } else {
+        format!("{}{}", count, tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blinker_with_comments() {
+        let mut universe = Universe::new(6, 5);
+        universe.set_cells_bulk(vec![1, 2, 2, 2, 3, 2], true);
+        assert_eq!(
+            universe.encode_as_rle_with_comments(
+                "John Conway",
+                "The smallest and most common oscillator.",
+                "Blinker",
+            ),
+            "#N Blinker\n\
+             #O John Conway\n\
+             #C The smallest and most common oscillator.\n\
+             #P 1 2\n\
+             x = 3, y = 1, rule = B3/S23\n\
+             3o!\n"
+        );
+    }
+
+    #[test]
+    fn glider_with_wrapped_description() {
+        let mut universe = Universe::new(10, 10);
+        universe.set_cells_bulk(vec![5, 2, 6, 3, 4, 4, 5, 4, 6, 4], true);
+        assert_eq!(
+            universe.encode_as_rle_with_comments(
+                "Richard K. Guy",
+                "The smallest, most common, and first discovered spaceship. \
+                 Diagonal, has period 4 and speed c/4.",
+                "Glider",
+            ),
+            "#N Glider\n\
+             #O Richard K. Guy\n\
+             #C The smallest, most common, and first discovered spaceship.\n\
+             #C Diagonal, has period 4 and speed c/4.\n\
+             #P 4 2\n\
+             x = 3, y = 3, rule = B3/S23\n\
+             bo$2bo$3o!\n"
+        );
+    }
+
+    #[test]
+    fn empty_fields_are_left_out() {
+        let universe = Universe::new(4, 4);
+        assert_eq!(
+            universe.encode_as_rle_with_comments("", "", ""),
+            "#P 0 0\nx = 1, y = 1, rule = B3/S23\n!\n"
+        );
+    }
+
+    #[test]
+    fn comment_lines_fit_in_seventy_characters() {
+        let description = "word ".repeat(60);
+        let rle = Universe::new(4, 4).encode_as_rle_with_comments("", &description, "");
+        let comments: Vec<&str> = rle.lines().filter(|l| l.starts_with("#C")).collect();
+        assert!(comments.len() > 1);
+        assert!(comments.iter().all(|line| line.len() <= 70));
+    }
+}
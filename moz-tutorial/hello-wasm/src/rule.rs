@@ -0,0 +1,192 @@
+use crate::{Cell, UniverseError};
+
+/// Parse an outer totalistic rule in B/S notation, e.g. "B3/S23",
+/// into its birth and survival neighbor counts.
+/// Counts above `max_neighbors` are rejected.
+pub(crate) fn parse_birth_survival(
+    rule: &str,
+    max_neighbors: u8,
+) -> Result<(Vec<u8>, Vec<u8>), UniverseError> {
+    let invalid = || UniverseError::InvalidRule(rule.to_owned());
+    let (mut birth, mut survival) = (None, None);
+    for part in rule.split('/') {
+        let mut chars = part.chars();
+        let target = match chars.next() {
+            Some('B') | Some('b') => &mut birth,
+            Some('S') | Some('s') => &mut survival,
+            _ => return Err(invalid()),
+        };
+        if target.is_some() {
+            return Err(invalid());
+        }
+        let counts = chars
+            .map(|c| match c.to_digit(10) {
+                Some(n) if n <= max_neighbors as u32 => Ok(n as u8),
+                _ => Err(invalid()),
+            })
+            .collect::<Result<Vec<u8>, _>>()?;
+        *target = Some(counts);
+    }
+    match (birth, survival) {
+        (Some(birth), Some(survival)) => Ok((birth, survival)),
+        _ => Err(invalid()),
+    }
+}
+
+/// The automaton that drives a Universe's `tick`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Rule {
+    /// An outer totalistic Life-like rule, e.g. Conway's Game of Life.
+    /// `table[live_neighbors * 2 + is_alive]` is 1 if the cell is live next tick.
+    Life { table: [u8; 18] },
+    /// Wireworld: electrons travelling along conductors.
+    Wireworld,
+    /// Brian's Brain: cells fire, then rest for a tick before they can fire again.
+    BriansBrain,
+}
+
+impl Default for Rule {
+    /// Conway's Game of Life, B3/S23.
+    fn default() -> Self {
+        Rule::from_birth_survival(&[3], &[2, 3])
+    }
+}
+
+impl std::fmt::Display for Rule {
+    /// Formats the rule in the form accepted by `Rule::parse`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rule::Life { table } => {
+                write!(f, "B")?;
+                for n in (0..9).filter(|n| table[n * 2] == 1) {
+                    write!(f, "{}", n)?;
+                }
+                write!(f, "/S")?;
+                for n in (0..9).filter(|n| table[n * 2 + 1] == 1) {
+                    write!(f, "{}", n)?;
+                }
+                Ok(())
+            }
+            Rule::Wireworld => write!(f, "wireworld"),
+            Rule::BriansBrain => write!(f, "brians-brain"),
+        }
+    }
+}
+
+impl Rule {
+    /// Parse a rule: "wireworld", "brians-brain", or a Life-like rule in B/S notation.
+    pub fn parse(rule: &str) -> Result<Self, UniverseError> {
+        match rule {
+            "wireworld" => Ok(Rule::Wireworld),
+            "brians-brain" => Ok(Rule::BriansBrain),
+            _ => {
+                let (birth, survival) = parse_birth_survival(rule, 8)?;
+                Ok(Rule::from_birth_survival(&birth, &survival))
+            }
+        }
+    }
+
+    /// A Life-like rule from its transition table; nonzero entries mean live.
+    pub fn from_table(table: &[u8; 18]) -> Rule {
+        Rule::Life {
+            table: table.map(|v| (v != 0) as u8),
+        }
+    }
+
+    /// The transition table of a Life-like rule, or None for other automata.
+    pub fn to_table(&self) -> Option<[u8; 18]> {
+        match self {
+            Rule::Life { table } => Some(*table),
+            _ => None,
+        }
+    }
+
+    fn from_birth_survival(birth: &[u8], survival: &[u8]) -> Rule {
+        let mut table = [0; 18];
+        for n in birth {
+            table[*n as usize * 2] = 1;
+        }
+        for n in survival {
+            table[*n as usize * 2 + 1] = 1;
+        }
+        Rule::Life { table }
+    }
+
+    /// Compute the next state of `cell`, given the states of its neighbors.
+    pub(crate) fn next(&self, cell: Cell, neighbors: impl Iterator<Item = Cell>) -> Cell {
+        match self {
+            Rule::Life { table } => {
+                let live_count = neighbors.filter(|n| n.is_live()).count();
+                let was_live = cell.is_live();
+                let is_live = table[live_count * 2 + was_live as usize] == 1;
+                Cell::transition(was_live, is_live)
+            }
+            Rule::Wireworld => match cell {
+                Cell::Head => Cell::Tail,
+                Cell::Tail => Cell::Conductor,
+                Cell::Conductor => match neighbors.filter(|n| *n == Cell::Head).count() {
+                    1 | 2 => Cell::Head,
+                    _ => Cell::Conductor,
+                },
+                _ => Cell::Dead,
+            },
+            Rule::BriansBrain => match cell {
+                c if c.is_live() => Cell::Refractory,
+                Cell::Refractory => Cell::Dead,
+                _ => match neighbors.filter(|n| n.is_live()).count() {
+                    2 => Cell::On,
+                    _ => Cell::Dead,
+                },
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conway_table() {
+        let mut conway = [0; 18];
+        // Born with 3 neighbors; survives with 2 or 3.
+        conway[3 * 2] = 1;
+        conway[2 * 2 + 1] = 1;
+        conway[3 * 2 + 1] = 1;
+        assert_eq!(Rule::parse("B3/S23").unwrap().to_table(), Some(conway));
+        assert_eq!(Rule::from_table(&conway), Rule::default());
+    }
+
+    #[test]
+    fn from_table_treats_nonzero_as_live() {
+        let mut table = [0; 18];
+        table[4] = 7;
+        assert_eq!(Rule::from_table(&table), Rule::parse("B2/S").unwrap());
+    }
+
+    #[test]
+    fn table_round_trips_through_display() {
+        let rule = Rule::parse("B36/S23").unwrap();
+        assert_eq!(rule.to_string(), "B36/S23");
+        assert_eq!(Rule::from_table(&rule.to_table().unwrap()), rule);
+        assert_eq!(Rule::Wireworld.to_table(), None);
+    }
+
+    #[test]
+    fn table_lookup_drives_next_state() {
+        let rule = Rule::default();
+        let neighbors =
+            |live: usize| (0..8).map(move |i| if i < live { Cell::Alive } else { Cell::Dead });
+        assert_eq!(rule.next(Cell::Dead, neighbors(3)), Cell::Newborn);
+        assert_eq!(rule.next(Cell::Alive, neighbors(2)), Cell::Alive);
+        assert_eq!(rule.next(Cell::Alive, neighbors(4)), Cell::Dying);
+        assert_eq!(rule.next(Cell::Dead, neighbors(2)), Cell::Dead);
+    }
+
+    #[test]
+    fn parse_rejects_malformed_rules() {
+        for rule in ["B3", "B9/S23", "X3/S23", "B3/B3"] {
+            assert!(Rule::parse(rule).is_err(), "{}", rule);
+        }
+    }
+}
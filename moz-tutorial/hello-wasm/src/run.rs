@@ -0,0 +1,239 @@
+use std::collections::{HashMap, HashSet};
+
+use wasm_bindgen::prelude::*;
+
+use crate::{now_ms, Op, Universe, UniverseError};
+
+/// The fewest ticks `Universe::benchmark_tps` measures, however short its duration.
+const MIN_BENCHMARK_TICKS: u32 = 10;
+
+/// When `Universe::run_for` stops.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum StopCondition {
+    /// After this many ticks.
+    Ticks(usize),
+    /// When no cells are live.
+    Extinct,
+    /// When a tick leaves the live cells unchanged.
+    Stable,
+    /// When more than this many cells are live.
+    PopulationAbove(usize),
+    /// When fewer than this many cells are live.
+    PopulationBelow(usize),
+    /// When the live cells repeat an earlier generation of the run.
+    CycleDetected,
+}
+
+impl std::fmt::Display for StopCondition {
+    /// Formats the condition in the form accepted by `StopCondition::parse`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StopCondition::Ticks(n) => write!(f, "ticks:{}", n),
+            StopCondition::Extinct => write!(f, "extinct"),
+            StopCondition::Stable => write!(f, "stable"),
+            StopCondition::PopulationAbove(n) => write!(f, "population-above:{}", n),
+            StopCondition::PopulationBelow(n) => write!(f, "population-below:{}", n),
+            StopCondition::CycleDetected => write!(f, "cycle"),
+        }
+    }
+}
+
+impl StopCondition {
+    /// Parse a condition: "extinct", "stable", "cycle", or "ticks:N",
+    /// "population-above:N", or "population-below:N".
+    pub fn parse(condition: &str) -> Result<Self, UniverseError> {
+        let invalid = || UniverseError::InvalidStopCondition(condition.to_owned());
+        let (name, count) = match condition.split_once(':') {
+            Some((name, count)) => (name, Some(count.parse().map_err(|_| invalid())?)),
+            None => (condition, None),
+        };
+        match (name, count) {
+            ("ticks", Some(n)) => Ok(StopCondition::Ticks(n)),
+            ("extinct", None) => Ok(StopCondition::Extinct),
+            ("stable", None) => Ok(StopCondition::Stable),
+            ("population-above", Some(n)) => Ok(StopCondition::PopulationAbove(n)),
+            ("population-below", Some(n)) => Ok(StopCondition::PopulationBelow(n)),
+            ("cycle", None) => Ok(StopCondition::CycleDetected),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// How a `Universe::run_for` call ended.
+#[wasm_bindgen]
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct RunResult {
+    ticks_run: u64,
+    stop_reason: StopCondition,
+    final_population: usize,
+}
+
+#[wasm_bindgen]
+impl RunResult {
+    pub fn ticks_run(&self) -> u64 {
+        self.ticks_run
+    }
+    /// The condition that stopped the run, as accepted by `run_for`;
+    /// "ticks:N" if it ran out of ticks.
+    #[wasm_bindgen(js_name = stop_reason)]
+    pub fn stop_reason_js(&self) -> String {
+        self.stop_reason.to_string()
+    }
+    pub fn final_population(&self) -> usize {
+        self.final_population
+    }
+}
+
+impl RunResult {
+    /// The condition that stopped the run; `Ticks(max_ticks)` if it ran out of ticks.
+    pub fn stop_reason(&self) -> StopCondition {
+        self.stop_reason
+    }
+}
+
+/// The outcome of `Universe::compute_period`.
+#[wasm_bindgen]
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct PeriodResult {
+    period: Option<u32>,
+    is_extinct: bool,
+    ticks_elapsed: u32,
+}
+
+#[wasm_bindgen]
+impl PeriodResult {
+    /// The number of ticks between repeats of the live cells: 1 for a still
+    /// life, 0 if they die out, or undefined if no repeat was found.
+    pub fn period(&self) -> Option<u32> {
+        self.period
+    }
+    pub fn is_extinct(&self) -> bool {
+        self.is_extinct
+    }
+    /// The ticks run before the period was found, or before giving up.
+    pub fn ticks_elapsed(&self) -> u32 {
+        self.ticks_elapsed
+    }
+}
+
+impl PeriodResult {
+    /// No period was found within `ticks_elapsed` ticks.
+    pub fn unknown(ticks_elapsed: u32) -> Self {
+        PeriodResult {
+            period: None,
+            is_extinct: false,
+            ticks_elapsed,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Find how often the live cells repeat, running up to `max_ticks` ticks on
+    /// a copy of this universe. States are compared by `content_hash`, so the
+    /// cells need to return to the same positions: a spaceship has no period
+    /// until it wraps around.
+    pub fn compute_period(&self, max_ticks: u32) -> PeriodResult {
+        let mut future = self.scratch_copy();
+        let mut seen = HashMap::new();
+        for tick in 0..=max_ticks {
+            if future.live_count == 0 {
+                return PeriodResult {
+                    period: Some(0),
+                    is_extinct: true,
+                    ticks_elapsed: tick,
+                };
+            }
+            if let Some(first) = seen.insert(future.content_hash(), tick) {
+                return PeriodResult {
+                    period: Some(tick - first),
+                    is_extinct: false,
+                    ticks_elapsed: tick,
+                };
+            }
+            if tick < max_ticks {
+                future.step();
+            }
+        }
+        PeriodResult::unknown(max_ticks)
+    }
+
+    /// Tick for about `duration_ms` milliseconds and return the ticks per
+    /// second achieved. At least 10 ticks run, however short the duration, so
+    /// the figure isn't dominated by timer resolution. The universe is left
+    /// in its state after the benchmark.
+    pub fn benchmark_tps(&mut self, duration_ms: f64) -> f64 {
+        let start = now_ms();
+        let mut ticks = 0;
+        let elapsed = loop {
+            let elapsed = now_ms() - start;
+            if ticks >= MIN_BENCHMARK_TICKS && elapsed >= duration_ms {
+                break elapsed;
+            }
+            self.step();
+            ticks += 1;
+        };
+        self.record(Op::TickN(ticks as usize));
+        if elapsed > 0.0 {
+            ticks as f64 * 1000.0 / elapsed
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// See `Universe::run_for`. `stop_condition` is parsed by `StopCondition::parse`.
+    #[wasm_bindgen(js_name = run_for)]
+    pub fn run_for_js(
+        &mut self,
+        max_ticks: usize,
+        stop_condition: &str,
+    ) -> Result<RunResult, UniverseError> {
+        Ok(self.run_for(max_ticks, StopCondition::parse(stop_condition)?))
+    }
+}
+
+impl Universe {
+    /// Tick until `stop_condition` is met, or `max_ticks` ticks have run.
+    /// Conditions on the population are checked before the first tick too, so
+    /// a run can stop after 0 ticks. Generations are compared by `content_hash`.
+    pub fn run_for(&mut self, max_ticks: usize, stop_condition: StopCondition) -> RunResult {
+        let mut seen = HashSet::new();
+        let mut previous = self.content_hash();
+        seen.insert(previous);
+        let mut ticks = 0;
+        let stopped = loop {
+            let met = match stop_condition {
+                StopCondition::Ticks(n) => ticks >= n,
+                StopCondition::Extinct => self.live_count == 0,
+                StopCondition::PopulationAbove(n) => self.live_count > n,
+                StopCondition::PopulationBelow(n) => self.live_count < n,
+                // Checked after each tick, below.
+                StopCondition::Stable | StopCondition::CycleDetected => false,
+            };
+            if met {
+                break stop_condition;
+            }
+            if ticks == max_ticks {
+                break StopCondition::Ticks(max_ticks);
+            }
+            self.step();
+            ticks += 1;
+            let hash = self.content_hash();
+            let met = match stop_condition {
+                StopCondition::Stable => hash == previous,
+                StopCondition::CycleDetected => !seen.insert(hash),
+                _ => false,
+            };
+            if met {
+                break stop_condition;
+            }
+            previous = hash;
+        };
+        self.record(Op::TickN(ticks));
+        RunResult {
+            ticks_run: ticks as u64,
+            stop_reason: stopped,
+            final_population: self.live_count,
+        }
+    }
+}
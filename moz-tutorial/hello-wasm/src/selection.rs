@@ -0,0 +1,99 @@
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{Cell, Coord, Op, Universe};
+
+/// Color of the selection outline.
+const SELECTION_COLOR: &str = "#ffffff";
+
+#[wasm_bindgen]
+impl Universe {
+    /// Select the `w` x `h` region with its top-left corner at (x, y),
+    /// clamped to the universe bounds.
+    pub fn select(&mut self, x: usize, y: usize, w: usize, h: usize) {
+        let x = x.min(self.width - 1);
+        let y = y.min(self.height - 1);
+        let w = w.clamp(1, self.width - x);
+        let h = h.clamp(1, self.height - y);
+        self.selection = Some((x, y, w, h));
+    }
+
+    pub fn deselect(&mut self) {
+        self.selection = None;
+    }
+
+    /// The selection as `[x, y, width, height]`, if any.
+    #[wasm_bindgen(js_name = selection)]
+    pub fn selection_js(&self) -> Option<Vec<u32>> {
+        self.selection().map(|(x, y, w, h)| vec![x, y, w, h])
+    }
+
+    /// Copy the selected region into a new Universe.
+    pub fn copy_selection(&self) -> Option<Universe> {
+        let (x, y, w, h) = self.selection?;
+        Some(self.subgrid(x, y, w, h))
+    }
+
+    /// Kill every cell in the selected region.
+    pub fn delete_selection(&mut self) {
+        if let Some((x, y, w, h)) = self.selection {
+            for sy in y..y + h {
+                for sx in x..x + w {
+                    self.put(Coord { y: sy, x: sx }, Cell::Dead);
+                }
+            }
+            self.record(Op::DeleteSelection { x, y, w, h });
+        }
+    }
+
+    /// The live cells in the `w` x `h` rectangle at (x, y), as a flat list of
+    /// `[x0, y0, x1, y1, ...]` in row-major order. The rectangle is clamped
+    /// to the universe bounds rather than wrapping.
+    pub fn live_cells_in_rect(&self, x: usize, y: usize, w: usize, h: usize) -> Vec<u32> {
+        self.cells_in_rect(x, y, w, h, true)
+    }
+
+    /// The dead cells in the rectangle; see `live_cells_in_rect`.
+    pub fn dead_cells_in_rect(&self, x: usize, y: usize, w: usize, h: usize) -> Vec<u32> {
+        self.cells_in_rect(x, y, w, h, false)
+    }
+
+    /// Render the universe, with a dashed outline around the selection.
+    pub fn render2d_with_selection(
+        &self,
+        canvas: &CanvasRenderingContext2d,
+    ) -> Result<(), JsValue> {
+        self.put_pixels(canvas, &self.pixels())?;
+        if let Some((x, y, w, h)) = self.selection {
+            let dash = js_sys::Array::of2(&JsValue::from(4.0), &JsValue::from(2.0));
+            canvas.save();
+            canvas.set_line_dash(&dash)?;
+            canvas.set_stroke_style_str(SELECTION_COLOR);
+            canvas.stroke_rect(x as f64, y as f64, w as f64, h as f64);
+            canvas.restore();
+        }
+        Ok(())
+    }
+}
+
+impl Universe {
+    fn cells_in_rect(&self, x: usize, y: usize, w: usize, h: usize, live: bool) -> Vec<u32> {
+        let x_end = x.saturating_add(w).min(self.width);
+        let y_end = y.saturating_add(h).min(self.height);
+        let mut coords = Vec::new();
+        for cy in y..y_end {
+            for cx in x..x_end {
+                if self[Coord { y: cy, x: cx }].is_live() == live {
+                    coords.extend_from_slice(&[cx as u32, cy as u32]);
+                }
+            }
+        }
+        coords
+    }
+
+    /// The selection as (x, y, width, height), if any.
+    pub fn selection(&self) -> Option<(u32, u32, u32, u32)> {
+        self.selection
+            .map(|(x, y, w, h)| (x as u32, y as u32, w as u32, h as u32))
+    }
+}
@@ -0,0 +1,99 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Op, Universe, UniverseError};
+
+// WebAssembly code can only address its own linear memory, so `content` cannot
+// alias an arbitrary SharedArrayBuffer. Instead, a shared universe keeps a byte
+// view of the buffer -- one byte per cell, nonzero for live -- and copies
+// through it explicitly. The copy is a single bulk transfer per sync, with no
+// per-cell calls across the JS boundary.
+
+#[wasm_bindgen]
+impl Universe {
+    /// Create a Universe attached to `buf`, which holds one byte per cell in
+    /// row-major order (nonzero for live), and load its current contents.
+    ///
+    /// A worker can write new generations into `buf`; call `sync_from_shared`
+    /// to load them, or `sync_to_shared` to publish this universe's state.
+    /// The buffer is not locked: writers and readers must agree on whose turn
+    /// it is, e.g. with an `Atomics`-updated generation counter alongside the
+    /// cells, so that a sync never observes a half-written generation.
+    pub fn from_shared_arraybuffer(
+        width: u32,
+        height: u32,
+        buf: js_sys::SharedArrayBuffer,
+    ) -> Result<Universe, JsValue> {
+        let mut universe = Universe::try_new(width as usize, height as usize)?;
+        universe.shared = Some(js_sys::Uint8Array::new(&buf));
+        universe.sync_from_shared()?;
+        Ok(universe)
+    }
+
+    /// Whether this universe is attached to a SharedArrayBuffer;
+    /// see `from_shared_arraybuffer`.
+    pub fn is_shared(&self) -> bool {
+        self.shared.is_some()
+    }
+
+    /// Load the cells from the shared buffer, if any. Cells keep their
+    /// transition colors, as if the buffer's state had been reached by a tick.
+    pub fn sync_from_shared(&mut self) -> Result<(), UniverseError> {
+        let bytes = match &self.shared {
+            Some(view) => self.checked_len(view)?.to_vec(),
+            None => return Ok(()),
+        };
+        self.load_live_bytes(&bytes)?;
+        self.record(Op::SyncFromShared(bytes));
+        Ok(())
+    }
+
+    /// Store the cells into the shared buffer, if any: 1 for live, 0 for dead.
+    pub fn sync_to_shared(&self) -> Result<(), UniverseError> {
+        if let Some(view) = &self.shared {
+            let view = self.checked_len(view)?;
+            let bytes: Vec<u8> = self
+                .content
+                .iter()
+                .map(|cell| cell.is_live() as u8)
+                .collect();
+            view.copy_from(&bytes);
+        }
+        Ok(())
+    }
+}
+
+impl Universe {
+    /// `view`, if it still has one byte per cell. After a resize it doesn't,
+    /// and every sync fails.
+    fn checked_len<'a>(
+        &self,
+        view: &'a js_sys::Uint8Array,
+    ) -> Result<&'a js_sys::Uint8Array, UniverseError> {
+        let found = view.length() as usize;
+        if found != self.content.len() {
+            return Err(UniverseError::SharedBufferMismatch {
+                expected: self.content.len(),
+                found,
+            });
+        }
+        Ok(view)
+    }
+
+    /// Set each cell from its byte in `bytes`, in row-major order: live if
+    /// nonzero, dead if zero, with the transition colors of a tick. Fails,
+    /// changing nothing, unless there is exactly one byte per cell.
+    pub(crate) fn load_live_bytes(&mut self, bytes: &[u8]) -> Result<(), UniverseError> {
+        if bytes.len() != self.content.len() {
+            return Err(UniverseError::SharedBufferMismatch {
+                expected: self.content.len(),
+                found: bytes.len(),
+            });
+        }
+        for (cell, &byte) in self.content.iter_mut().zip(bytes) {
+            *cell = Cell::transition(cell.is_live(), byte != 0);
+        }
+        self.recount_live();
+        self.debug_validate();
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{checked_cells, Universe, UniverseError};
+
+#[wasm_bindgen]
+impl Universe {
+    /// See `Universe::export_as_spritesheet`.
+    #[wasm_bindgen(js_name = export_as_spritesheet)]
+    pub fn export_as_spritesheet_js(
+        &mut self,
+        frames: u32,
+        cell_size: u32,
+    ) -> Result<js_sys::Uint8Array, UniverseError> {
+        let png = self.export_as_spritesheet(frames, cell_size)?;
+        Ok(js_sys::Uint8Array::from(&png[..]))
+    }
+}
+
+impl Universe {
+    /// A PNG image of the next `frames` generations side by side, starting with
+    /// the current one, each cell `cell_size` pixels square in the colors of
+    /// `render2d`. The universe is left `frames` ticks ahead. `frames` and
+    /// `cell_size` are at least 1. Fails if the image would have more pixels
+    /// than a universe can have cells. Only available with the `png-export`
+    /// feature.
+    pub fn export_as_spritesheet(
+        &mut self,
+        frames: u32,
+        cell_size: u32,
+    ) -> Result<Vec<u8>, UniverseError> {
+        let (frames, cell_size) = (frames.max(1) as usize, cell_size.max(1) as usize);
+        let frame_width = self.width.saturating_mul(cell_size);
+        let sheet_width = frame_width.saturating_mul(frames);
+        let sheet_height = self.height.saturating_mul(cell_size);
+        let mut sheet = vec![0u8; checked_cells(sheet_width, sheet_height)? * 4];
+        for frame in 0..frames {
+            let pixels = self.display_pixels();
+            for (i, rgba) in pixels.chunks_exact(4).enumerate() {
+                let (x, y) = (i % self.width, i / self.width);
+                for py in y * cell_size..(y + 1) * cell_size {
+                    let row = py * sheet_width + frame * frame_width;
+                    for px in x * cell_size..(x + 1) * cell_size {
+                        sheet[(row + px) * 4..(row + px + 1) * 4].copy_from_slice(rgba);
+                    }
+                }
+            }
+            self.tick();
+        }
+
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, sheet_width as u32, sheet_height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&sheet))
+            .expect("encoding a valid image into memory cannot fail");
+        Ok(png)
+    }
+}
@@ -0,0 +1,473 @@
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{fft, islands, Universe, UniverseError};
+
+/// How many generations of population history a Universe keeps.
+pub(crate) const POPULATION_HISTORY_LEN: usize = 1024;
+
+/// Statistics about a single tick; see `Universe::tick_measured`.
+#[wasm_bindgen]
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub struct TickStats {
+    births: u32,
+    deaths: u32,
+    survivors: u32,
+    pub(crate) duration_ms: f64,
+}
+
+impl TickStats {
+    /// Count one cell's transition.
+    pub(crate) fn count(&mut self, was_live: bool, is_live: bool) {
+        match (was_live, is_live) {
+            (false, true) => self.births += 1,
+            (true, false) => self.deaths += 1,
+            (true, true) => self.survivors += 1,
+            (false, false) => (),
+        }
+    }
+}
+
+/// A summary of how live cells group together; see `Universe::cluster_stats`.
+#[wasm_bindgen]
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub struct ClusterStats {
+    cluster_count: u32,
+    mean_cluster_size: f64,
+    max_cluster_size: u32,
+    mean_centroid_x: f64,
+    mean_centroid_y: f64,
+    spatial_spread: f64,
+}
+
+#[wasm_bindgen]
+impl ClusterStats {
+    pub fn cluster_count(&self) -> u32 {
+        self.cluster_count
+    }
+    pub fn mean_cluster_size(&self) -> f64 {
+        self.mean_cluster_size
+    }
+    pub fn max_cluster_size(&self) -> u32 {
+        self.max_cluster_size
+    }
+    /// The mean of the clusters' centroids, each weighted equally.
+    pub fn mean_centroid_x(&self) -> f64 {
+        self.mean_centroid_x
+    }
+    pub fn mean_centroid_y(&self) -> f64 {
+        self.mean_centroid_y
+    }
+    /// The standard deviation of the clusters' centroids: their root-mean-square
+    /// distance from `mean_centroid_x`, `mean_centroid_y`.
+    pub fn spatial_spread(&self) -> f64 {
+        self.spatial_spread
+    }
+}
+
+#[wasm_bindgen]
+impl TickStats {
+    pub fn births_u32(&self) -> u32 {
+        self.births
+    }
+    pub fn deaths_u32(&self) -> u32 {
+        self.deaths
+    }
+    pub fn survivors_u32(&self) -> u32 {
+        self.survivors
+    }
+    pub fn population_before_u32(&self) -> u32 {
+        self.survivors + self.deaths
+    }
+    pub fn population_after_u32(&self) -> u32 {
+        self.survivors + self.births
+    }
+    /// Wall-clock time the tick took.
+    pub fn duration_ms(&self) -> f64 {
+        self.duration_ms
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// See `Universe::neighbor_histogram`.
+    #[wasm_bindgen(js_name = neighbor_histogram)]
+    pub fn neighbor_histogram_js(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(&self.neighbor_histogram()[..])
+    }
+
+    /// See `Universe::neighbor_histogram_live_only`.
+    #[wasm_bindgen(js_name = neighbor_histogram_live_only)]
+    pub fn neighbor_histogram_live_only_js(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(&self.neighbor_histogram_live_only()[..])
+    }
+
+    /// Summarize the clusters of live cells, connected through their edges
+    /// (`connectivity` 4) or edges and corners (8). All zero if there are no
+    /// live cells.
+    pub fn cluster_stats(&self, connectivity: u8) -> Result<ClusterStats, UniverseError> {
+        let clusters = self.clusters(1, islands::diagonals(connectivity)?);
+        if clusters.is_empty() {
+            return Ok(ClusterStats::default());
+        }
+        let count = clusters.len() as f64;
+        let centroids: Vec<(f64, f64)> = clusters
+            .iter()
+            .map(|cluster| {
+                let n = cluster.len() as f64;
+                let x: usize = cluster.iter().map(|c| c.x).sum();
+                let y: usize = cluster.iter().map(|c| c.y).sum();
+                (x as f64 / n, y as f64 / n)
+            })
+            .collect();
+        let mean_x = centroids.iter().map(|c| c.0).sum::<f64>() / count;
+        let mean_y = centroids.iter().map(|c| c.1).sum::<f64>() / count;
+        let variance = centroids
+            .iter()
+            .map(|(x, y)| (x - mean_x).powi(2) + (y - mean_y).powi(2))
+            .sum::<f64>()
+            / count;
+        Ok(ClusterStats {
+            cluster_count: clusters.len() as u32,
+            mean_cluster_size: self.live_count as f64 / count,
+            max_cluster_size: clusters.iter().map(Vec::len).max().unwrap_or(0) as u32,
+            mean_centroid_x: mean_x,
+            mean_centroid_y: mean_y,
+            spatial_spread: variance.sqrt(),
+        })
+    }
+
+    /// The live cell count of each recent generation, oldest first,
+    /// up to `POPULATION_HISTORY_LEN` entries.
+    pub fn get_population_history(&self) -> Vec<u32> {
+        self.population_history.iter().map(|&n| n as u32).collect()
+    }
+
+    /// The average change in live cell count per tick over the last `window`
+    /// ticks, or NaN if the population history is shorter than that.
+    pub fn growth_rate(&self, window: usize) -> f64 {
+        match self.population_back(&[0, window]) {
+            Some([now, then]) if window > 0 => (now - then) / window as f64,
+            _ => f64::NAN,
+        }
+    }
+
+    /// The change in growth rate between the last two `window`-tick spans,
+    /// per tick squared: the second difference of population. NaN if the
+    /// population history is shorter than `2 * window` ticks.
+    pub fn growth_acceleration(&self, window: usize) -> f64 {
+        match self.population_back(&[0, window, 2 * window]) {
+            Some([now, mid, then]) if window > 0 => {
+                (now - 2.0 * mid + then) / (window * window) as f64
+            }
+            _ => f64::NAN,
+        }
+    }
+
+    /// The sample variance of the live cell count over the last `window`
+    /// generations. Near 0 for stable or nearly periodic patterns; high for
+    /// chaotic ones. NaN if `window` is less than 2, or the population history
+    /// is shorter than `window` generations.
+    pub fn population_variance(&self, window: usize) -> f64 {
+        match self.recent_populations(window) {
+            Some(populations) if window >= 2 => {
+                let mean = populations.iter().sum::<f64>() / window as f64;
+                let squares: f64 = populations.iter().map(|p| (p - mean).powi(2)).sum();
+                squares / (window - 1) as f64
+            }
+            _ => f64::NAN,
+        }
+    }
+
+    /// The coefficient of variation of the live cell count over the last
+    /// `window` generations: the standard deviation (from
+    /// `population_variance`) over the mean. NaN as for `population_variance`,
+    /// or if the mean is 0.
+    pub fn population_cv(&self, window: usize) -> f64 {
+        let mean = match self.recent_populations(window) {
+            Some(populations) if window > 0 => populations.iter().sum::<f64>() / window as f64,
+            _ => return f64::NAN,
+        };
+        if mean == 0.0 {
+            return f64::NAN;
+        }
+        self.population_variance(window).sqrt() / mean
+    }
+
+    /// The Pearson correlation between the live cells and the live cells
+    /// shifted right by `k` columns (wrapping around each row), for each `k` in
+    /// `0..=max_lag`. Entry 0 is always 1; high values at small lags mean live
+    /// cells are clustered horizontally. A universe that is all live or all
+    /// dead counts as perfectly correlated at every lag.
+    ///
+    /// Lags repeat with period `width`. A few lags are counted directly;
+    /// more are computed from each row's power spectrum, in `O(width * height
+    /// * log(width))` time however large `max_lag` is.
+    pub fn spatial_autocorrelation(&self, max_lag: usize) -> Vec<f64> {
+        let cells = self.content.len() as f64;
+        let density = self.live_count as f64 / cells;
+        let variance = density - density * density;
+        if variance <= 0.0 {
+            return vec![1.0; max_lag + 1];
+        }
+        let distinct = (max_lag + 1).min(self.width);
+        let matches = if distinct <= DIRECT_AUTOCORRELATION_LAGS {
+            self.row_matches_direct(distinct)
+        } else {
+            self.row_matches_fft()
+        };
+        (0..=max_lag)
+            .map(|k| (matches[k % self.width] as f64 / cells - density * density) / variance)
+            .collect()
+    }
+
+    /// The live neighbor counts of the live cells, summed, so each pair of
+    /// adjacent live cells counts twice. Isolated cells add nothing; a dense
+    /// crowd adds up to 8 per cell.
+    pub fn total_neighbor_pressure(&self) -> u64 {
+        self.cells()
+            .filter(|(_, cell)| cell.is_live())
+            .map(|(here, _)| self.live_neighbors(here) as u64)
+            .sum()
+    }
+
+    /// `total_neighbor_pressure` per cell.
+    pub fn mean_neighbor_pressure(&self) -> f64 {
+        self.total_neighbor_pressure() as f64 / self.content.len() as f64
+    }
+
+    /// The binary Shannon entropy of the live fraction within the square of
+    /// cells at most `window_radius` away from each cell, in row-major order.
+    /// Each value is in [0, 1]: 0 where the window is uniformly live or dead,
+    /// 1 where it is half live. Windows are clipped at the universe's edges.
+    pub fn local_entropy_map(&self, window_radius: usize) -> Vec<f64> {
+        self.window_live_counts(window_radius)
+            .into_iter()
+            .map(|(live, cells)| binary_entropy(live as f64 / cells as f64))
+            .collect()
+    }
+
+    /// Render `local_entropy_map(window_radius)` in grayscale, from black for
+    /// uniform regions to white for evenly mixed ones.
+    pub fn render2d_entropy_map(
+        &self,
+        canvas: &CanvasRenderingContext2d,
+        window_radius: usize,
+    ) -> Result<(), JsValue> {
+        let entropy = self.local_entropy_map(window_radius);
+        let pixels = self.pixels_with(|i, _| {
+            let level = (entropy[i] * 255.0).round() as u32;
+            level << 24 | level << 16 | level << 8 | 0xFF
+        });
+        self.put_pixels(canvas, &pixels)
+    }
+
+    /// The binary Shannon entropy of the live fraction within the horizontal
+    /// run of `window` cells centered on each cell, in row-major order. Like
+    /// `local_entropy_map` but one row tall, so it picks out vertical stripes
+    /// of activity that a square window averages away. Windows are clipped at
+    /// the universe's left and right edges; a `window` of 0 counts as 1.
+    pub fn rolling_entropy(&self, window: usize) -> Vec<f64> {
+        let w = self.width;
+        let window = window.max(1);
+        let mut entropy = Vec::with_capacity(self.content.len());
+        // live_before[x]: live cells left of x in the current row.
+        let mut live_before = vec![0usize; w + 1];
+        for row in self.content.chunks(w) {
+            for (x, cell) in row.iter().enumerate() {
+                live_before[x + 1] = live_before[x] + cell.is_live() as usize;
+            }
+            for x in 0..w {
+                let left = x.saturating_sub(window / 2);
+                let right = (x + window - window / 2).min(w);
+                let live = live_before[right] - live_before[left];
+                entropy.push(binary_entropy(live as f64 / (right - left) as f64));
+            }
+        }
+        entropy
+    }
+
+    /// Render `rolling_entropy(window)` as a gradient from blue for uniform
+    /// runs to red for evenly mixed ones, with dead cells drawn darker so the
+    /// pattern stays visible beneath it.
+    pub fn render2d_entropy_gradient(
+        &self,
+        canvas: &CanvasRenderingContext2d,
+        window: usize,
+    ) -> Result<(), JsValue> {
+        let entropy = self.rolling_entropy(window);
+        let pixels = self.pixels_with(|i, cell| {
+            let shade = if cell.is_live() { 255.0 } else { 96.0 };
+            let red = (entropy[i] * shade).round() as u32;
+            let blue = ((1.0 - entropy[i]) * shade).round() as u32;
+            red << 24 | blue << 8 | 0xFF
+        });
+        self.put_pixels(canvas, &pixels)
+    }
+}
+
+/// Lag counts at or below which `spatial_autocorrelation` skips the FFT.
+const DIRECT_AUTOCORRELATION_LAGS: usize = 32;
+
+/// The entropy, in bits, of a coin that lands heads with probability `p`.
+fn binary_entropy(p: f64) -> f64 {
+    if p <= 0.0 || p >= 1.0 {
+        return 0.0;
+    }
+    -(p * p.log2() + (1.0 - p) * (1.0 - p).log2())
+}
+
+impl Universe {
+    /// For each lag `k` below `lags`, the number of live cells whose
+    /// neighbor `k` columns to the right (wrapping around the row) is also live.
+    fn row_matches_direct(&self, lags: usize) -> Vec<usize> {
+        (0..lags)
+            .map(|k| {
+                self.content
+                    .chunks_exact(self.width)
+                    .map(|row| {
+                        (0..self.width)
+                            .filter(|&x| row[x].is_live() && row[(x + k) % self.width].is_live())
+                            .count()
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// `row_matches_direct` for every lag below `width`, by the
+    /// Wiener-Khinchin theorem: the rows' summed power spectrum transforms back
+    /// into their summed autocorrelation. Rows are zero-padded to a power of
+    /// two at least twice as long, giving the linear autocorrelation; lag `k`
+    /// of the wrapped one adds lag `width - k` of the linear one.
+    fn row_matches_fft(&self) -> Vec<usize> {
+        let w = self.width;
+        let n = (2 * w).next_power_of_two();
+        let mut power = vec![(0.0, 0.0); n];
+        let mut row_buf = vec![(0.0, 0.0); n];
+        for row in self.content.chunks_exact(w) {
+            for (slot, cell) in row_buf.iter_mut().zip(row) {
+                *slot = (cell.is_live() as u8 as f64, 0.0);
+            }
+            row_buf[w..].iter_mut().for_each(|slot| *slot = (0.0, 0.0));
+            fft::fft(&mut row_buf, false);
+            for (sum, (re, im)) in power.iter_mut().zip(&row_buf) {
+                sum.0 += re * re + im * im;
+            }
+        }
+        fft::fft(&mut power, true);
+        let linear = |k: usize| power[k].0.round() as usize;
+        (0..w)
+            .map(|k| {
+                if k == 0 {
+                    linear(0)
+                } else {
+                    linear(k) + linear(w - k)
+                }
+            })
+            .collect()
+    }
+
+    /// For each cell, in row-major order, the live cells and all cells in the
+    /// square at most `radius` away, clipped at the universe's edges. Takes
+    /// O(width * height) time whatever the radius, using a 2D prefix sum.
+    pub(crate) fn window_live_counts(&self, radius: usize) -> Vec<(usize, usize)> {
+        let (w, h) = (self.width, self.height);
+        // live_before[y * (w + 1) + x]: live cells above and left of (x, y).
+        let mut live_before = vec![0usize; (w + 1) * (h + 1)];
+        for y in 0..h {
+            for x in 0..w {
+                let live = self.content[y * w + x].is_live() as usize;
+                live_before[(y + 1) * (w + 1) + x + 1] =
+                    live + live_before[y * (w + 1) + x + 1] + live_before[(y + 1) * (w + 1) + x]
+                        - live_before[y * (w + 1) + x];
+            }
+        }
+        let mut counts = Vec::with_capacity(self.content.len());
+        for y in 0..h {
+            let (top, bottom) = (y.saturating_sub(radius), (y + radius + 1).min(h));
+            for x in 0..w {
+                let (left, right) = (x.saturating_sub(radius), (x + radius + 1).min(w));
+                let live = live_before[bottom * (w + 1) + right]
+                    + live_before[top * (w + 1) + left]
+                    - live_before[top * (w + 1) + right]
+                    - live_before[bottom * (w + 1) + left];
+                counts.push((live, (bottom - top) * (right - left)));
+            }
+        }
+        counts
+    }
+
+    /// The number of cells, live or dead, with exactly 0 through 8 live neighbors.
+    pub fn neighbor_histogram(&self) -> [u32; 9] {
+        self.histogram_of(|_| true)
+    }
+
+    /// The number of live cells with exactly 0 through 8 live neighbors.
+    pub fn neighbor_histogram_live_only(&self) -> [u32; 9] {
+        self.histogram_of(|live| live)
+    }
+
+    /// Record the live cell count after a tick. `before` is the count just
+    /// before it, recorded too if the history is empty.
+    pub(crate) fn push_population(&mut self, before: usize, after: usize) {
+        if self.population_history.is_empty() {
+            self.population_history.push_back(before);
+        }
+        self.population_history.push_back(after);
+        while self.population_history.len() > POPULATION_HISTORY_LEN {
+            self.population_history.pop_front();
+        }
+    }
+
+    /// The populations of the last `window` generations, oldest first, if the
+    /// history goes back that far.
+    fn recent_populations(&self, window: usize) -> Option<Vec<f64>> {
+        let skip = self.population_history.len().checked_sub(window)?;
+        Some(
+            self.population_history
+                .iter()
+                .skip(skip)
+                .map(|&n| n as f64)
+                .collect(),
+        )
+    }
+
+    /// The populations `ago[i]` generations before the latest, if the history
+    /// goes back that far.
+    fn population_back<const N: usize>(&self, ago: &[usize; N]) -> Option<[f64; N]> {
+        let latest = self.population_history.len().checked_sub(1)?;
+        let mut populations = [0.0; N];
+        for (population, &ago) in populations.iter_mut().zip(ago) {
+            *population = self.population_history[latest.checked_sub(ago)?] as f64;
+        }
+        Some(populations)
+    }
+
+    fn histogram_of(&self, include: impl Fn(bool) -> bool) -> [u32; 9] {
+        let mut histogram = [0; 9];
+        for (coord, cell) in self {
+            if include(cell.is_live()) {
+                histogram[self.live_neighbors(coord)] += 1;
+            }
+        }
+        histogram
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn row_matches_fft_agrees_with_direct() {
+        // Wide enough that spatial_autocorrelation takes the FFT path.
+        let mut universe = Universe::new(100, 40);
+        universe.randomize(7);
+        assert!(universe.width > DIRECT_AUTOCORRELATION_LAGS);
+        assert_eq!(
+            universe.row_matches_fft(),
+            universe.row_matches_direct(universe.width)
+        );
+    }
+}
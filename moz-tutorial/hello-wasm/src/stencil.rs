@@ -0,0 +1,75 @@
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{Cell, Op, Universe, UniverseError};
+
+/// Overlay color (0xRRGGBBAA) for forced-dead cells.
+const FORCED_DEAD_COLOR: u32 = 0x800000FF;
+/// Overlay color (0xRRGGBBAA) for forced-live cells.
+const FORCED_LIVE_COLOR: u32 = 0x008000FF;
+
+// Other than these, stencil entries are 0, for cells that follow the rules.
+
+/// Stencil entry for a cell that is dead after every tick.
+pub(crate) const FORCED_DEAD: u8 = 1;
+/// Stencil entry for a cell that is alive after every tick.
+pub(crate) const FORCED_LIVE: u8 = 2;
+
+#[wasm_bindgen]
+impl Universe {
+    /// Force cells dead or alive after each tick, regardless of the rules.
+    /// `stencil` has one entry per cell, in row-major order: 0 leaves the cell
+    /// free, 1 forces it dead, and 2 forces it alive. Resizing the universe
+    /// clears the stencil.
+    pub fn set_stencil(&mut self, stencil: Vec<u8>) -> Result<(), UniverseError> {
+        if stencil.len() != self.content.len() {
+            return Err(UniverseError::InvalidStencil(format!(
+                "{} entries for {} cells",
+                stencil.len(),
+                self.content.len()
+            )));
+        }
+        if let Some(entry) = stencil.iter().find(|&&entry| entry > FORCED_LIVE) {
+            return Err(UniverseError::InvalidStencil(format!(
+                "unknown entry {}",
+                entry
+            )));
+        }
+        self.record(Op::SetStencil(stencil.clone()));
+        self.stencil = Some(stencil);
+        Ok(())
+    }
+
+    pub fn clear_stencil(&mut self) {
+        self.record(Op::ClearStencil);
+        self.stencil = None;
+    }
+
+    /// Render the universe like `render2d`, but with forced-dead cells in dark
+    /// red and forced-live cells in dark green, whatever their current state.
+    pub fn render2d_with_stencil_overlay(
+        &self,
+        canvas: &CanvasRenderingContext2d,
+    ) -> Result<(), JsValue> {
+        let pixels =
+            self.pixels_with(
+                |i, cell| match self.stencil.as_ref().map(|stencil| stencil[i]) {
+                    Some(FORCED_DEAD) => FORCED_DEAD_COLOR,
+                    Some(FORCED_LIVE) => FORCED_LIVE_COLOR,
+                    _ => cell as u32,
+                },
+            );
+        self.put_pixels(canvas, &pixels)
+    }
+}
+
+impl Universe {
+    /// `cell`, the next state of the `index`th cell, after applying the stencil.
+    pub(crate) fn stencilled(&self, index: usize, cell: Cell) -> Cell {
+        match self.stencil.as_ref().map(|stencil| stencil[index]) {
+            Some(FORCED_DEAD) => Cell::Dead,
+            Some(FORCED_LIVE) => Cell::Alive,
+            _ => cell,
+        }
+    }
+}
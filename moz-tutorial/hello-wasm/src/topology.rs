@@ -0,0 +1,248 @@
+use crate::{Coord, UniverseError};
+
+/// How the edges of a Universe connect to each other.
+#[derive(PartialEq, Eq, Debug, Default, Clone, Copy)]
+pub(crate) enum Topology {
+    /// Opposite edges are joined.
+    #[default]
+    Torus,
+    /// Cells beyond the edges are dead.
+    Bounded,
+    /// One pair of opposite edges is joined; cells beyond the other pair are dead.
+    /// `wrap_horizontal` joins the left and right edges.
+    Cylinder { wrap_horizontal: bool },
+    /// Left and right edges are joined with the y axis reversed;
+    /// top and bottom edges are joined normally.
+    MoebiusStrip,
+    /// Left and right edges are joined with the y axis reversed;
+    /// top and bottom edges are joined normally.
+    KleinBottle,
+}
+
+impl Topology {
+    /// Parse a topology name: "torus", "bounded", "cylinder-h", "cylinder-v",
+    /// "moebius", or "klein".
+    pub(crate) fn parse(name: &str) -> Result<Self, UniverseError> {
+        match name {
+            "torus" => Ok(Topology::Torus),
+            "bounded" => Ok(Topology::Bounded),
+            "cylinder-h" => Ok(Topology::Cylinder {
+                wrap_horizontal: true,
+            }),
+            "cylinder-v" => Ok(Topology::Cylinder {
+                wrap_horizontal: false,
+            }),
+            "moebius" => Ok(Topology::MoebiusStrip),
+            "klein" => Ok(Topology::KleinBottle),
+            _ => Err(UniverseError::InvalidTopology(name.to_owned())),
+        }
+    }
+
+    /// Whether the surface has a consistent notion of left and right.
+    /// Rotating the grid of a non-orientable surface would move its twisted seam.
+    pub(crate) fn is_orientable(self) -> bool {
+        matches!(
+            self,
+            Topology::Torus | Topology::Bounded | Topology::Cylinder { .. }
+        )
+    }
+
+    /// The equivalent topology after a quarter turn of the grid.
+    pub(crate) fn rotated(self) -> Self {
+        match self {
+            Topology::Cylinder { wrap_horizontal } => Topology::Cylinder {
+                wrap_horizontal: !wrap_horizontal,
+            },
+            other => other,
+        }
+    }
+
+    /// Find the cell at (x, y), which may lie up to one cell beyond an edge.
+    /// Returns None if there is no such cell.
+    pub(crate) fn resolve(self, x: isize, y: isize, width: usize, height: usize) -> Option<Coord> {
+        let (w, h) = (width as isize, height as isize);
+        let (x, y) = match self {
+            Topology::Torus => (x.rem_euclid(w), y.rem_euclid(h)),
+            Topology::Bounded => {
+                if x < 0 || x >= w || y < 0 || y >= h {
+                    return None;
+                }
+                (x, y)
+            }
+            Topology::Cylinder { wrap_horizontal } => {
+                if wrap_horizontal {
+                    if y < 0 || y >= h {
+                        return None;
+                    }
+                    (x.rem_euclid(w), y)
+                } else {
+                    if x < 0 || x >= w {
+                        return None;
+                    }
+                    (x, y.rem_euclid(h))
+                }
+            }
+            // As specified, both reflect y across the left/right seam and wrap
+            // the top and bottom edges normally, so they resolve identically.
+            Topology::MoebiusStrip | Topology::KleinBottle => {
+                let y = y.rem_euclid(h);
+                if x < 0 || x >= w {
+                    (x.rem_euclid(w), h - 1 - y)
+                } else {
+                    (x, y)
+                }
+            }
+        };
+        Some(Coord {
+            y: y as usize,
+            x: x as usize,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Universe;
+
+    /// A lightweight spaceship, travelling left at c/2.
+    const LWSS: [&str; 4] = [".O..O", "O....", "O...O", "OOOO."];
+    /// A lightweight spaceship, travelling up at c/2.
+    const LWSS_UP: [&str; 5] = [".OOO", "O..O", "...O", "...O", "O.O."];
+    /// A glider, travelling down and to the right at c/4.
+    const GLIDER: [&str; 3] = [".O.", "..O", "OOO"];
+
+    /// A `width` x `height` universe with `pattern` drawn with its top-left at (x, y).
+    fn universe_with(
+        width: usize,
+        height: usize,
+        x: usize,
+        y: usize,
+        pattern: &[&str],
+    ) -> Universe {
+        let mut universe = Universe::new(width, height);
+        for (dy, row) in pattern.iter().enumerate() {
+            for (dx, _) in row.match_indices('O') {
+                universe.set_cell(x + dx, y + dy, true);
+            }
+        }
+        universe
+    }
+
+    /// The live cells of `universe`, as sorted (x, y) pairs.
+    fn live(universe: &Universe) -> Vec<(usize, usize)> {
+        let mut cells: Vec<(usize, usize)> = universe
+            .cells()
+            .filter(|(_, cell)| cell.is_live())
+            .map(|(coord, _)| (coord.x, coord.y))
+            .collect();
+        cells.sort();
+        cells
+    }
+
+    #[test]
+    fn moebius_reflects_y_across_left_and_right_edges() {
+        let moebius = Topology::MoebiusStrip;
+        assert_eq!(moebius.resolve(-1, 1, 10, 6), Some(Coord { y: 4, x: 9 }));
+        assert_eq!(moebius.resolve(10, 0, 10, 6), Some(Coord { y: 5, x: 0 }));
+        assert_eq!(moebius.resolve(3, -1, 10, 6), Some(Coord { y: 5, x: 3 }));
+        assert_eq!(moebius.resolve(3, 2, 10, 6), Some(Coord { y: 2, x: 3 }));
+    }
+
+    #[test]
+    fn moebius_spaceship_returns_mirrored() {
+        // At c/2, one lap of a 20-wide strip takes 40 ticks.
+        let (width, height) = (20, 9);
+        let mut universe = universe_with(width, height, 8, 1, &LWSS);
+        universe.set_topology("moebius").unwrap();
+        let start = live(&universe);
+        let mut mirrored: Vec<(usize, usize)> =
+            start.iter().map(|&(x, y)| (x, height - 1 - y)).collect();
+        mirrored.sort();
+        assert_ne!(start, mirrored);
+
+        universe.tick_n(2 * width);
+        assert_eq!(live(&universe), mirrored);
+        // A second lap flips it back.
+        universe.tick_n(2 * width);
+        assert_eq!(live(&universe), start);
+    }
+
+    #[test]
+    fn klein_wraps_top_and_bottom_normally() {
+        let klein = Topology::KleinBottle;
+        assert_eq!(klein.resolve(2, -1, 10, 6), Some(Coord { y: 5, x: 2 }));
+        assert_eq!(klein.resolve(2, 6, 10, 6), Some(Coord { y: 0, x: 2 }));
+        assert_eq!(klein.resolve(-1, 0, 10, 6), Some(Coord { y: 5, x: 9 }));
+    }
+
+    #[test]
+    fn klein_blinker_reappears_reflected_on_right_edge() {
+        // A vertical blinker on the left edge, above the middle row.
+        let mut universe = universe_with(10, 8, 0, 1, &["O", "O", "O"]);
+        universe.set_topology("klein").unwrap();
+        let start = live(&universe);
+
+        // Its horizontal phase reaches across the seam, where row 2 becomes row 5.
+        universe.tick();
+        assert_eq!(live(&universe), [(0, 2), (1, 2), (9, 5)]);
+        universe.tick();
+        assert_eq!(live(&universe), start);
+    }
+
+    #[test]
+    fn transforms_require_orientable_topology() {
+        for topology in ["moebius", "klein"] {
+            let mut universe = Universe::new(4, 4);
+            universe.set_topology(topology).unwrap();
+            assert!(universe.rotate_cw().is_err());
+            assert!(universe.flip_horizontal().is_err());
+        }
+        let mut universe = Universe::new(4, 4);
+        assert!(universe.rotate_cw().is_ok());
+        assert!(universe.flip_horizontal().is_ok());
+    }
+
+    #[test]
+    fn cylinder_wraps_only_one_axis() {
+        let horizontal = Topology::Cylinder {
+            wrap_horizontal: true,
+        };
+        assert_eq!(horizontal.resolve(-1, 2, 10, 6), Some(Coord { y: 2, x: 9 }));
+        assert_eq!(horizontal.resolve(2, -1, 10, 6), None);
+        let vertical = Topology::Cylinder {
+            wrap_horizontal: false,
+        };
+        assert_eq!(vertical.resolve(-1, 2, 10, 6), None);
+        assert_eq!(vertical.resolve(2, -1, 10, 6), Some(Coord { y: 5, x: 2 }));
+    }
+
+    #[test]
+    fn glider_wraps_around_horizontal_cylinder() {
+        // 32 ticks carry the glider 8 cells right, around the 8-wide cylinder,
+        // and 8 cells down.
+        let mut universe = universe_with(8, 30, 2, 2, &GLIDER);
+        universe.set_topology("cylinder-h").unwrap();
+        let start = live(&universe);
+        universe.tick_n(32);
+        let moved: Vec<(usize, usize)> = start.iter().map(|&(x, y)| (x, y + 8)).collect();
+        assert_eq!(live(&universe), moved);
+    }
+
+    #[test]
+    fn spaceship_escapes_through_dead_border_of_horizontal_cylinder() {
+        // One lap of the 20-tall universe takes 40 ticks at c/2.
+        let mut vertical = universe_with(11, 20, 4, 8, &LWSS_UP);
+        vertical.set_topology("cylinder-v").unwrap();
+        let start = live(&vertical);
+        vertical.tick_n(40);
+        assert_eq!(live(&vertical), start);
+
+        let mut horizontal = universe_with(11, 20, 4, 8, &LWSS_UP);
+        horizontal.set_topology("cylinder-h").unwrap();
+        horizontal.tick_n(40);
+        // It hit the top edge rather than coming back up through the bottom.
+        assert_ne!(live(&horizontal), start);
+        assert!(live(&horizontal).iter().all(|&(_, y)| y < 10));
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::VecDeque;
+
+use wasm_bindgen::prelude::*;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{Cell, Universe};
+
+/// Recent generations kept for `Universe::render2d_trail`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub(crate) struct TrailBuffer {
+    capacity: usize,
+    /// Previous generations' cells, newest first.
+    frames: VecDeque<Vec<Cell>>,
+}
+
+impl TrailBuffer {
+    /// Forget the saved generations, as when the grid is replaced.
+    pub(crate) fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Replace each saved generation with `regrid(frame)`, as when the grid grows.
+    pub(crate) fn regrid(&mut self, regrid: impl Fn(&[Cell]) -> Vec<Cell>) {
+        for frame in self.frames.iter_mut() {
+            *frame = regrid(frame);
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Keep the last `trail_length` generations for `render2d_trail`, starting
+    /// from the next tick; 0 stops keeping them.
+    pub fn enable_trail_buffer(&mut self, trail_length: usize) {
+        self.trail = if trail_length == 0 {
+            None
+        } else {
+            Some(TrailBuffer {
+                capacity: trail_length,
+                frames: VecDeque::with_capacity(trail_length),
+            })
+        };
+    }
+
+    /// Render the current generation over fading copies of the live cells of
+    /// up to `trail_length` previous ones, from the trail buffer (see
+    /// `enable_trail_buffer`). The generation `age + 1` ticks back is blended in
+    /// with opacity `fade_alpha ^ (age + 1)`, so with `fade_alpha` below 1,
+    /// older generations are fainter. Current live cells are drawn opaque.
+    pub fn render2d_trail(
+        &self,
+        canvas: &CanvasRenderingContext2d,
+        trail_length: usize,
+        fade_alpha: f64,
+    ) -> Result<(), JsValue> {
+        let mut colors: Vec<[f64; 3]> = vec![rgb(Cell::Dead as u32); self.content.len()];
+        let frames = self.trail.iter().flat_map(|trail| trail.frames.iter());
+        let recent: Vec<&Vec<Cell>> = frames.take(trail_length).collect();
+        // Oldest first, so newer generations blend over older ones.
+        for (age, frame) in recent.iter().enumerate().rev() {
+            let alpha = fade_alpha.clamp(0.0, 1.0).powi(age as i32 + 1);
+            for (color, &cell) in colors.iter_mut().zip(frame.iter()) {
+                if cell.is_live() {
+                    let over = rgb(cell as u32);
+                    for (channel, over) in color.iter_mut().zip(over) {
+                        *channel += (over - *channel) * alpha;
+                    }
+                }
+            }
+        }
+        let pixels = self.pixels_with(|i, cell| {
+            if cell.is_live() {
+                return cell as u32;
+            }
+            let [r, g, b] = colors[i].map(|channel| channel.round() as u32);
+            r << 24 | g << 16 | b << 8 | 0xFF
+        });
+        self.put_pixels(canvas, &pixels)
+    }
+}
+
+impl Universe {
+    /// Save the current generation into the trail buffer, if enabled, just
+    /// before a tick replaces it.
+    pub(crate) fn push_trail(&mut self) {
+        if let Some(trail) = &mut self.trail {
+            if trail.frames.len() == trail.capacity {
+                trail.frames.pop_back();
+            }
+            trail.frames.push_front(self.content.clone());
+        }
+    }
+}
+
+/// The red, green, and blue channels of a `0xRRGGBBAA` color.
+fn rgb(color: u32) -> [f64; 3] {
+    let [r, g, b, _] = color.to_be_bytes();
+    [r, g, b].map(f64::from)
+}
@@ -0,0 +1,687 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::{checked_cells, Cell, Coord, LogLevel, Op, Universe, UniverseError};
+
+/// Configuration for growing a universe as its live cells approach the edges.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub(crate) struct AutoExpand {
+    margin: usize,
+    max_width: usize,
+    max_height: usize,
+    /// Whether we've already warned about reaching the maximum size.
+    warned: bool,
+}
+
+/// How `Universe::superimpose` combines source and destination cells.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum SuperimposeMode {
+    /// Live if either cell is live.
+    Or,
+    /// Live if both cells are live.
+    And,
+    /// Live if exactly one cell is live.
+    Xor,
+    /// Copy the source cell, live or dead.
+    Replace,
+}
+
+impl SuperimposeMode {
+    /// Parse a mode name: "or", "and", "xor", or "replace".
+    pub fn parse(mode: &str) -> Result<Self, UniverseError> {
+        match mode {
+            "or" => Ok(SuperimposeMode::Or),
+            "and" => Ok(SuperimposeMode::And),
+            "xor" => Ok(SuperimposeMode::Xor),
+            "replace" => Ok(SuperimposeMode::Replace),
+            _ => Err(UniverseError::InvalidMode(mode.to_owned())),
+        }
+    }
+
+    fn combine(self, destination: bool, source: bool) -> bool {
+        match self {
+            SuperimposeMode::Or => destination || source,
+            SuperimposeMode::And => destination && source,
+            SuperimposeMode::Xor => destination != source,
+            SuperimposeMode::Replace => source,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// Overlay `other` at offset (dx, dy); see `Universe::superimpose`.
+    /// `mode` is "or", "and", "xor", or "replace".
+    #[wasm_bindgen(js_name = superimpose)]
+    pub fn superimpose_js(
+        &mut self,
+        other: &Universe,
+        dx: i32,
+        dy: i32,
+        mode: &str,
+    ) -> Result<(), UniverseError> {
+        self.superimpose(other, dx, dy, SuperimposeMode::parse(mode)?)
+    }
+
+    /// Smooth the live cells by majority vote: each cell becomes live if more
+    /// than half the cells in the square at most `radius` away are live, and
+    /// dead otherwise. The square is clipped at the universe's edges. This is
+    /// not a tick; one or two passes after `randomize` give smoother blobs.
+    pub fn apply_blur_filter(&mut self, radius: u32) {
+        self.record(Op::ApplyBlurFilter(radius));
+        let counts = self.window_live_counts(radius as usize);
+        for (cell, (live, cells)) in self.content.iter_mut().zip(counts) {
+            *cell = if 2 * live > cells {
+                Cell::Alive
+            } else {
+                Cell::Dead
+            };
+        }
+        self.recount_live();
+        self.debug_validate();
+    }
+
+    /// Shrink the universe to the bounding box of its live cells.
+    /// An all-dead universe shrinks to a single dead cell.
+    pub fn crop_to_bounding_box(&mut self) {
+        let (x, y, width, height) = self.bounding_box().unwrap_or((0, 0, 1, 1));
+        let cropped = self.subgrid(x, y, width, height);
+        self.replace_content(width, height, cropped.content);
+        self.record(Op::CropToBoundingBox);
+    }
+
+    /// Move the live cells so their bounding box is centered in the universe,
+    /// keeping its dimensions. Every cell moves by the same offset, wrapping
+    /// around the edges, along with its age, stencil entry and history.
+    /// Returns false, changing nothing, if no cells are live.
+    pub fn center_on_live_cells(&mut self) -> bool {
+        let (x, y, width, height) = match self.bounding_box() {
+            Some(bounds) => bounds,
+            None => return false,
+        };
+        let dx = ((self.width - width) / 2) as isize - x as isize;
+        let dy = ((self.height - height) / 2) as isize - y as isize;
+        self.shift(dx, dy);
+        self.record(Op::CenterOnLiveCells);
+        true
+    }
+
+    /// After each tick, pad the universe by `margin` dead cells whenever a live
+    /// cell is within `margin` cells of an edge, up to `max_width` x `max_height`.
+    pub fn set_auto_expand(&mut self, margin: usize, max_width: usize, max_height: usize) {
+        self.record(Op::SetAutoExpand {
+            margin,
+            max_width,
+            max_height,
+        });
+        self.auto_expand = Some(AutoExpand {
+            margin,
+            max_width,
+            max_height,
+            warned: false,
+        });
+    }
+
+    pub fn disable_auto_expand(&mut self) {
+        self.record(Op::DisableAutoExpand);
+        self.auto_expand = None;
+    }
+
+    /// Surround the universe with `cells` dead rows and columns on every side.
+    pub fn pad(&mut self, cells: usize) -> Result<(), UniverseError> {
+        let margin = cells.saturating_mul(2);
+        self.resize(
+            self.width.saturating_add(margin),
+            self.height.saturating_add(margin),
+            "center",
+        )
+    }
+
+    /// A new universe holding `nx` x `ny` copies of this one, side by side.
+    /// If either count is zero, the result is a single dead cell.
+    /// Fails if the result would exceed `MAX_CELLS`.
+    pub fn tile(&self, nx: usize, ny: usize) -> Result<Universe, UniverseError> {
+        if nx == 0 || ny == 0 {
+            return Ok(self.blank_like(1, 1));
+        }
+        let width = self.width.saturating_mul(nx);
+        let height = self.height.saturating_mul(ny);
+        checked_cells(width, height)?;
+        let mut tiled = self.blank_like(width, height);
+        self.tile_into(&mut tiled, 0, 0, nx, ny);
+        Ok(tiled)
+    }
+
+    /// A new universe `factor` times smaller in each dimension (rounding down,
+    /// but at least 1x1), where each cell is alive if at least half of the
+    /// `factor` x `factor` block it replaces was. A factor of 0 counts as 1,
+    /// and one larger than both dimensions counts as the larger of them.
+    pub fn downscale(&self, factor: usize) -> Universe {
+        let factor = factor.clamp(1, self.width.max(self.height));
+        let (width, height) = ((self.width / factor).max(1), (self.height / factor).max(1));
+        let mut small = self.blank_like(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let live = (0..factor * factor)
+                    .filter(|i| {
+                        self[Coord {
+                            y: y * factor + i / factor,
+                            x: x * factor + i % factor,
+                        }]
+                        .is_live()
+                    })
+                    .count();
+                if 2 * live >= factor * factor {
+                    small.put(Coord { y, x }, Cell::Alive);
+                }
+            }
+        }
+        small
+    }
+
+    /// A new universe `factor` times larger in each dimension, with each cell
+    /// repeated as a `factor` x `factor` block. A factor of 0 counts as 1.
+    pub fn upscale(&self, factor: usize) -> Result<Universe, UniverseError> {
+        let factor = factor.max(1);
+        let width = self.width.saturating_mul(factor);
+        let height = self.height.saturating_mul(factor);
+        checked_cells(width, height)?;
+        let mut large = self.blank_like(width, height);
+        for (coord, cell) in self {
+            for i in 0..factor * factor {
+                large.put(
+                    Coord {
+                        y: coord.y * factor + i / factor,
+                        x: coord.x * factor + i % factor,
+                    },
+                    cell,
+                );
+            }
+        }
+        Ok(large)
+    }
+
+    /// A new square universe, a power of 2 on each side between 4 and 4096,
+    /// sized to hold about `target_cells` live cells when randomized at
+    /// `density` with `seed`; see `randomize_with_density`.
+    pub fn auto_resize_to_fit(target_cells: usize, density: f64, seed: u64) -> Universe {
+        let side = (target_cells as f64 / density.clamp(0.0, 1.0)).sqrt();
+        let side = 2f64.powf(side.log2().round());
+        let side = if side.is_nan() {
+            4
+        } else {
+            side.clamp(4.0, 4096.0) as usize
+        };
+        let mut universe = Universe::new(side, side);
+        universe.randomize_with_density(seed, density);
+        universe
+    }
+
+    /// Write `nx` x `ny` copies of this universe into `target`, starting at
+    /// (offset_x, offset_y) and wrapping around `target`'s edges.
+    pub fn tile_into(
+        &self,
+        target: &mut Universe,
+        offset_x: usize,
+        offset_y: usize,
+        nx: usize,
+        ny: usize,
+    ) {
+        for ty in 0..ny {
+            for tx in 0..nx {
+                for (coord, cell) in self {
+                    target.put(
+                        Coord {
+                            y: offset_y + ty * self.height + coord.y,
+                            x: offset_x + tx * self.width + coord.x,
+                        },
+                        cell,
+                    );
+                }
+            }
+        }
+        target.record(Op::TileInto {
+            width: self.width,
+            cells: self.content.clone(),
+            offset_x,
+            offset_y,
+            nx,
+            ny,
+        });
+    }
+}
+
+impl Universe {
+    /// If auto-expand is on and a live cell is within its margin of an edge,
+    /// grow the universe by the margin on each side, up to the maximum size;
+    /// see `set_auto_expand`. Warns once on reaching the maximum.
+    pub(crate) fn expand_if_near_boundary(&mut self) {
+        let config = match self.auto_expand {
+            Some(config) => config,
+            None => return,
+        };
+        let margin = config.margin;
+        let near_edge = match self.bounding_box() {
+            Some((x, y, w, h)) => {
+                x < margin
+                    || y < margin
+                    || x + w + margin > self.width
+                    || y + h + margin > self.height
+            }
+            None => false,
+        };
+        if !near_edge {
+            return;
+        }
+
+        let width = (self.width + 2 * margin)
+            .min(config.max_width)
+            .max(self.width);
+        let height = (self.height + 2 * margin)
+            .min(config.max_height)
+            .max(self.height);
+        if (width, height) == (self.width, self.height) {
+            if !config.warned {
+                self.log(
+                    LogLevel::Warn,
+                    &format!(
+                        "warning: universe reached its maximum size of {}x{}",
+                        config.max_width, config.max_height
+                    ),
+                );
+                self.auto_expand = Some(AutoExpand {
+                    warned: true,
+                    ..config
+                });
+            }
+            return;
+        }
+        if let Err(err) = self.grow_centered(width, height) {
+            self.log(
+                LogLevel::Warn,
+                &format!("warning: cannot expand universe: {}", err),
+            );
+        }
+    }
+
+    /// Grow the grid to `width` x `height`, at least its current size, with
+    /// the old grid centered in it. Unlike `resize`, this happens within a
+    /// tick: it records no op, and carries the per-cell state -- ages, visits,
+    /// stencil, Gray-Scott concentrations, trail, undo and step-back history --
+    /// into the new grid, with the added cells dead and untouched.
+    fn grow_centered(&mut self, width: usize, height: usize) -> Result<(), UniverseError> {
+        checked_cells(width, height)?;
+        let (dx, dy) = ((width - self.width) / 2, (height - self.height) / 2);
+        let grid = Regrid {
+            old_width: self.width,
+            width,
+            height,
+            dx,
+            dy,
+        };
+        self.content = grid.apply(&self.content, Cell::Dead);
+        if let Some(ages) = &mut self.ages {
+            *ages = grid.apply(ages, 0);
+        }
+        if let Some(visits) = &mut self.visits {
+            *visits = grid.apply(visits, 0);
+        }
+        if let Some(stencil) = &mut self.stencil {
+            *stencil = grid.apply(stencil, 0);
+        }
+        if self.gray_scott.is_some() {
+            self.content_f32 = grid.apply(&self.content_f32, (1.0, 0.0));
+        }
+        if let Some(trail) = &mut self.trail {
+            trail.regrid(|frame| grid.apply(frame, Cell::Dead));
+        }
+        for previous in self.history.iter_mut() {
+            *previous = grid.apply(previous, Cell::Dead);
+        }
+        if let Some(previous) = &mut self.prev_content {
+            *previous = grid.apply(previous, Cell::Dead);
+        }
+        if let Some((x, y, w, h)) = self.selection {
+            self.selection = Some((x + dx, y + dy, w, h));
+        }
+        if let Some((x, y)) = self.position {
+            self.position = Some((x - dx as i64, y - dy as i64));
+        }
+        self.width = width;
+        self.height = height;
+        self.debug_validate();
+        Ok(())
+    }
+
+    /// Move every cell `dx` columns right and `dy` rows down, wrapping around
+    /// the edges, carrying the same per-cell state as `grow_centered`.
+    fn shift(&mut self, dx: isize, dy: isize) {
+        if let Some((x, y)) = self.position {
+            self.position = Some((x - dx as i64, y - dy as i64));
+        }
+        let width = self.width;
+        let dx = dx.rem_euclid(width as isize) as usize;
+        let dy = dy.rem_euclid(self.height as isize) as usize;
+        rotate_grid(&mut self.content, width, dx, dy);
+        if let Some(ages) = &mut self.ages {
+            rotate_grid(ages, width, dx, dy);
+        }
+        if let Some(visits) = &mut self.visits {
+            rotate_grid(visits, width, dx, dy);
+        }
+        if let Some(stencil) = &mut self.stencil {
+            rotate_grid(stencil, width, dx, dy);
+        }
+        if self.gray_scott.is_some() {
+            rotate_grid(&mut self.content_f32, width, dx, dy);
+        }
+        if let Some(trail) = &mut self.trail {
+            trail.regrid(|frame| {
+                let mut frame = frame.to_vec();
+                rotate_grid(&mut frame, width, dx, dy);
+                frame
+            });
+        }
+        for previous in self.history.iter_mut() {
+            rotate_grid(previous, width, dx, dy);
+        }
+        if let Some(previous) = &mut self.prev_content {
+            rotate_grid(previous, width, dx, dy);
+        }
+        if let Some((x, y, w, h)) = self.selection {
+            self.selection = Some(((x + dx) % width, (y + dy) % self.height, w, h));
+        }
+        self.debug_validate();
+    }
+
+    /// Overlay `other` with its top-left corner at the signed offset (dx, dy),
+    /// wrapping around the edges. Only the cells under `other` change.
+    /// Fails if `other` is larger than this universe in either dimension.
+    pub fn superimpose(
+        &mut self,
+        other: &Universe,
+        dx: i32,
+        dy: i32,
+        mode: SuperimposeMode,
+    ) -> Result<(), UniverseError> {
+        if other.width > self.width || other.height > self.height {
+            return Err(UniverseError::PatternTooLarge);
+        }
+        let x0 = (dx as isize).rem_euclid(self.width as isize) as usize;
+        let y0 = (dy as isize).rem_euclid(self.height as isize) as usize;
+        for (coord, source) in other {
+            let here = Coord {
+                y: y0 + coord.y,
+                x: x0 + coord.x,
+            };
+            let live = mode.combine(self[here].is_live(), source.is_live());
+            self.put(here, if live { Cell::Alive } else { Cell::Dead });
+        }
+        self.record(Op::Superimpose {
+            width: other.width,
+            cells: other.content.clone(),
+            dx,
+            dy,
+            mode,
+        });
+        Ok(())
+    }
+
+    /// The smallest rectangle `(x, y, width, height)` containing every live cell,
+    /// or None if there are no live cells. The rectangle does not wrap around edges.
+    pub fn bounding_box(&self) -> Option<(usize, usize, usize, usize)> {
+        let mut live = self.cells().filter(|(_, cell)| cell.is_live());
+        let (first, _) = live.next()?;
+        let (mut min_x, mut max_x, mut min_y, mut max_y) = (first.x, first.x, first.y, first.y);
+        for (coord, _) in live {
+            min_x = min_x.min(coord.x);
+            max_x = max_x.max(coord.x);
+            min_y = min_y.min(coord.y);
+            max_y = max_y.max(coord.y);
+        }
+        Some((min_x, min_y, max_x - min_x + 1, max_y - min_y + 1))
+    }
+
+    /// Copy the `width` x `height` region with its top-left corner at (x, y),
+    /// wrapping around the edges, into a new Universe with the same rule and topology.
+    pub fn subgrid(&self, x: usize, y: usize, width: usize, height: usize) -> Universe {
+        let mut sub = self.blank_like(width, height);
+        for sy in 0..height {
+            for sx in 0..width {
+                sub.put(
+                    Coord { y: sy, x: sx },
+                    self[Coord {
+                        y: y + sy,
+                        x: x + sx,
+                    }],
+                );
+            }
+        }
+        sub
+    }
+
+    /// An all-dead universe of the given size, with the same rule and topology as this one.
+    pub(crate) fn blank_like(&self, width: usize, height: usize) -> Universe {
+        let mut blank = Universe::new(width, height);
+        blank.rule = self.rule.clone();
+        blank.topology = self.topology;
+        blank.log_level = self.log_level;
+        blank
+    }
+
+    /// A quiet copy of the cells, rule, and topology, to run experiments on.
+    pub(crate) fn scratch_copy(&self) -> Universe {
+        let mut copy = self.blank_like(self.width, self.height);
+        copy.content = self.content.clone();
+        copy.recount_live();
+        copy.stencil = self.stencil.clone();
+        copy.log_level = LogLevel::None;
+        copy
+    }
+}
+
+/// Placement of a grid inside a larger one, for `Universe::grow_centered`.
+struct Regrid {
+    old_width: usize,
+    width: usize,
+    height: usize,
+    /// Where the old grid's top-left corner lands.
+    dx: usize,
+    dy: usize,
+}
+
+impl Regrid {
+    /// `old`, one entry per cell of the old grid, moved into the new one,
+    /// with `fill` everywhere else.
+    fn apply<T: Clone>(&self, old: &[T], fill: T) -> Vec<T> {
+        let mut grown = vec![fill; self.width * self.height];
+        for (y, row) in old.chunks_exact(self.old_width).enumerate() {
+            let start = (y + self.dy) * self.width + self.dx;
+            grown[start..start + self.old_width].clone_from_slice(row);
+        }
+        grown
+    }
+}
+
+/// Move each entry of the row-major grid `cells`, `width` entries per row,
+/// `dx` columns right and `dy` rows down, wrapping around the edges.
+fn rotate_grid<T>(cells: &mut [T], width: usize, dx: usize, dy: usize) {
+    cells.rotate_right(dy * width);
+    for row in cells.chunks_exact_mut(width) {
+        row.rotate_right(dx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::perimeter;
+
+    const GLIDER: [&str; 3] = [".O.", "..O", "OOO"];
+
+    #[test]
+    fn crop_undoes_pad() {
+        let glider = Universe::from_pattern(&GLIDER).unwrap();
+        let mut universe = Universe::from_pattern(&GLIDER).unwrap();
+        universe.pad(5).unwrap();
+        assert_eq!((universe.get_width(), universe.get_height()), (13, 13));
+        universe.crop_to_bounding_box();
+        assert_eq!((universe.get_width(), universe.get_height()), (3, 3));
+        assert_eq!(universe.live_coords(), glider.live_coords());
+    }
+
+    #[test]
+    fn crop_keeps_generation() {
+        let mut universe = Universe::new(8, 8);
+        universe.tick_n(3);
+        universe.crop_to_bounding_box();
+        assert_eq!((universe.get_width(), universe.get_height()), (1, 1));
+        assert_eq!(universe.live_cell_count(), 0);
+        assert_eq!(universe.get_generation(), 3);
+    }
+
+    #[test]
+    fn pad_shifts_content() {
+        let mut universe = Universe::from_pattern(&GLIDER).unwrap();
+        universe.pad(2).unwrap();
+        assert_eq!((universe.get_width(), universe.get_height()), (7, 7));
+        assert_eq!(universe.bounding_box(), Some((2, 2, 3, 3)));
+    }
+
+    #[test]
+    fn pad_does_not_change_centered_glider() {
+        let mut original = Universe::new(20, 20);
+        original.set_cells_bulk(vec![9, 8, 10, 9, 8, 10, 9, 10, 10, 10], true);
+        let mut padded = original.scratch_copy();
+        padded.pad(10).unwrap();
+
+        original.tick_n(10);
+        padded.tick_n(10);
+        assert_eq!(padded.live_cell_count(), 5);
+        assert_eq!(
+            padded.subgrid(10, 10, 20, 20).live_coords(),
+            original.live_coords()
+        );
+    }
+
+    #[test]
+    fn tile_repeats_live_cells() {
+        let mut universe = Universe::new(7, 5);
+        universe.randomize(3);
+        assert!(universe.live_cell_count() > 0);
+        let tiled = universe.tile(2, 2).unwrap();
+        assert_eq!((tiled.get_width(), tiled.get_height()), (14, 10));
+        assert_eq!(tiled.live_cell_count(), 4 * universe.live_cell_count());
+        assert_eq!(
+            tiled.subgrid(7, 5, 7, 5).live_coords(),
+            universe.live_coords()
+        );
+    }
+
+    #[test]
+    fn tile_with_no_copies_is_single_dead_cell() {
+        let universe = Universe::from_pattern(&GLIDER).unwrap();
+        for (nx, ny) in [(0, 2), (2, 0)] {
+            let tiled = universe.tile(nx, ny).unwrap();
+            assert_eq!((tiled.get_width(), tiled.get_height()), (1, 1));
+            assert_eq!(tiled.live_cell_count(), 0);
+        }
+    }
+
+    #[test]
+    fn tile_rejects_oversized_result() {
+        let universe = Universe::new(4096, 1);
+        assert!(universe.tile(1, 4097).is_err());
+    }
+
+    #[test]
+    fn tile_into_wraps_around_target() {
+        let glider = Universe::from_pattern(&GLIDER).unwrap();
+        let mut target = Universe::new(5, 5);
+        glider.tile_into(&mut target, 4, 4, 1, 1);
+        assert_eq!(target.live_cell_count(), 5);
+        // The glider's bottom-right cell lands at (4 + 2, 4 + 2), wrapped to (1, 1).
+        assert!(target[Coord { y: 1, x: 1 }].is_live());
+    }
+
+    #[test]
+    fn downscale_clamps_huge_factor() {
+        let mut universe = Universe::new(9, 5);
+        universe.randomize(3);
+        let huge = universe.downscale(usize::MAX);
+        assert_eq!((huge.width, huge.height), (1, 1));
+        assert_eq!(huge.live_coords(), universe.downscale(9).live_coords());
+    }
+
+    #[test]
+    fn center_on_live_cells_moves_centroid_to_center() {
+        let mut universe = Universe::new(20, 16);
+        let glider = Universe::from_pattern(&GLIDER).unwrap();
+        glider.tile_into(&mut universe, 1, 12, 1, 1);
+        assert!(universe.center_on_live_cells());
+        assert_eq!((universe.get_width(), universe.get_height()), (20, 16));
+        assert_eq!(universe.live_cell_count(), 5);
+        let (x, y) = universe.centroid().unwrap();
+        assert!((x - 9.5).abs() <= 1.0, "x = {}", x);
+        assert!((y - 7.5).abs() <= 1.0, "y = {}", y);
+    }
+
+    #[test]
+    fn center_on_live_cells_keeps_states_and_history() {
+        let mut universe = Universe::new(12, 5);
+        universe.set_cells_bulk(vec![1, 1, 2, 1], true);
+        universe.set_rule_brians_brain();
+        universe.enable_step_back();
+        universe.tick();
+        // Firing cells above and below the pair, which is now refractory.
+        assert_eq!(universe.bounding_box(), Some((1, 0, 2, 3)));
+        assert!(universe.center_on_live_cells());
+        assert_eq!(universe.bounding_box(), Some((5, 1, 2, 3)));
+        for x in [5, 6] {
+            assert_eq!(universe[Coord { y: 1, x }], Cell::On);
+            assert_eq!(universe[Coord { y: 2, x }], Cell::Refractory);
+            assert_eq!(universe[Coord { y: 3, x }], Cell::On);
+        }
+        // Stepping back gives the generation before the tick, moved the same way.
+        assert!(universe.step_back());
+        assert_eq!(universe.live_coords(), vec![5, 2, 6, 2]);
+    }
+
+    #[test]
+    fn center_on_live_cells_of_empty_universe_fails() {
+        let mut universe = Universe::new(5, 5);
+        assert!(!universe.center_on_live_cells());
+    }
+
+    #[test]
+    fn blur_reduces_perimeter_of_noise() {
+        let mut universe = Universe::new(48, 48);
+        universe.randomize_with_density(6, 0.5);
+        let noisy = perimeter(&universe);
+        universe.apply_blur_filter(1);
+        assert!(perimeter(&universe) < noisy);
+    }
+
+    #[test]
+    fn blur_with_zero_radius_changes_nothing() {
+        let mut universe = Universe::new(12, 12);
+        universe.randomize(8);
+        let before = universe.live_coords();
+        universe.apply_blur_filter(0);
+        assert_eq!(universe.live_coords(), before);
+    }
+
+    #[test]
+    fn blur_is_majority_vote() {
+        let mut universe =
+            Universe::from_pattern(&["O.....", "......", "...OOO", "...OOO"]).unwrap();
+        universe.apply_blur_filter(1);
+        // The lone cell is outvoted, as is the block's top-left corner, which
+        // has 4 live cells in a window of 9. Windows are clipped at the edges,
+        // so the bottom-left corner has 4 live cells in a window of 6.
+        assert_eq!(universe.live_cell_count(), 5);
+        assert!(!universe[Coord { y: 0, x: 0 }].is_live());
+        assert!(!universe[Coord { y: 2, x: 3 }].is_live());
+        assert!(universe[Coord { y: 3, x: 3 }].is_live());
+    }
+}
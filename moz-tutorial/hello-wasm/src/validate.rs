@@ -0,0 +1,119 @@
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+#[wasm_bindgen]
+impl Universe {
+    /// See `Universe::validate`.
+    #[wasm_bindgen(js_name = validate)]
+    pub fn validate_js(&self) -> js_sys::Array {
+        self.validate().into_iter().map(JsValue::from).collect()
+    }
+}
+
+impl Universe {
+    /// Check the universe's internal invariants, returning a description of
+    /// each one that is violated. A consistent universe returns no messages.
+    pub fn validate(&self) -> Vec<String> {
+        let mut violations = Vec::new();
+        let cells = self.width * self.height;
+        if self.content.len() != cells {
+            violations.push(format!(
+                "content.len() ({}) != width * height ({})",
+                self.content.len(),
+                cells
+            ));
+        }
+        let live = self.content.iter().filter(|cell| cell.is_live()).count();
+        if self.live_count != live {
+            violations.push(format!(
+                "cached live count ({}) != actual live count ({})",
+                self.live_count, live
+            ));
+        }
+        if let Some(ages) = &self.ages {
+            if ages.len() != self.content.len() {
+                violations.push(format!(
+                    "ages.len() ({}) != content.len() ({}) with age tracking enabled",
+                    ages.len(),
+                    self.content.len()
+                ));
+            }
+        }
+        if let Some(visits) = &self.visits {
+            if visits.len() != self.content.len() {
+                violations.push(format!(
+                    "visits.len() ({}) != content.len() ({}) with heat map tracking enabled",
+                    visits.len(),
+                    self.content.len()
+                ));
+            }
+        }
+        let concentrations = if self.gray_scott.is_some() {
+            self.content.len()
+        } else {
+            0
+        };
+        if self.content_f32.len() != concentrations {
+            violations.push(format!(
+                "content_f32.len() ({}) != {} with gray_scott {:?}",
+                self.content_f32.len(),
+                concentrations,
+                self.gray_scott
+            ));
+        }
+        if self.history.len() > self.undo_capacity {
+            violations.push(format!(
+                "history.len() ({}) > undo_capacity ({})",
+                self.history.len(),
+                self.undo_capacity
+            ));
+        }
+        for (i, previous) in self.history.iter().enumerate() {
+            if previous.len() != self.content.len() {
+                violations.push(format!(
+                    "history[{}].len() ({}) != content.len() ({})",
+                    i,
+                    previous.len(),
+                    self.content.len()
+                ));
+            }
+        }
+        if let Some(previous) = &self.prev_content {
+            if previous.len() != self.content.len() {
+                violations.push(format!(
+                    "prev_content.len() ({}) != content.len() ({})",
+                    previous.len(),
+                    self.content.len()
+                ));
+            }
+        }
+        if let Some(stencil) = &self.stencil {
+            if stencil.len() != self.content.len() {
+                violations.push(format!(
+                    "stencil.len() ({}) != content.len() ({})",
+                    stencil.len(),
+                    self.content.len()
+                ));
+            }
+        }
+        if let Some((x, y, w, h)) = self.selection {
+            if x + w > self.width || y + h > self.height {
+                violations.push(format!(
+                    "selection ({}, {}, {}, {}) exceeds {}x{} universe",
+                    x, y, w, h, self.width, self.height
+                ));
+            }
+        }
+        violations
+    }
+
+    /// In debug builds, panic if any invariant is violated.
+    pub(crate) fn debug_validate(&self) {
+        debug_assert!(
+            self.validate().is_empty(),
+            "invalid universe: {:?}",
+            self.validate()
+        );
+    }
+}
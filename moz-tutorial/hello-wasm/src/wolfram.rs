@@ -0,0 +1,202 @@
+use std::collections::VecDeque;
+
+use wasm_bindgen::prelude::*;
+use web_sys::{CanvasRenderingContext2d, ImageData};
+
+use crate::{checked_cells, Cell, UniverseError};
+
+/// A 1D elementary cellular automaton, displayed as a scrolling 2D history:
+/// each row is one generation, with the newest at the bottom.
+#[wasm_bindgen]
+pub struct WolframUniverse {
+    rule: u8,
+    width: usize,
+    height: usize,
+    rows: VecDeque<Vec<bool>>,
+}
+
+impl WolframUniverse {
+    /// Create a universe following the given Wolfram rule number.
+    /// Without a seed row, the first generation has only its center cell live.
+    /// A seed row is truncated or padded with dead cells to `width`.
+    /// Fails if either dimension is zero or the history would exceed `MAX_CELLS`.
+    pub fn new(
+        rule: u8,
+        width: usize,
+        height: usize,
+        seed_row: Option<Vec<bool>>,
+    ) -> Result<Self, UniverseError> {
+        checked_cells(width, height)?;
+        let mut row = seed_row.unwrap_or_else(|| {
+            let mut row = vec![false; width];
+            row[width / 2] = true;
+            row
+        });
+        row.resize(width, false);
+
+        let mut rows = VecDeque::with_capacity(height);
+        rows.push_back(row);
+        Ok(WolframUniverse {
+            rule,
+            width,
+            height,
+            rows,
+        })
+    }
+
+    /// The generations currently retained, oldest first.
+    pub fn rows(&self) -> impl Iterator<Item = &Vec<bool>> {
+        self.rows.iter()
+    }
+}
+
+#[wasm_bindgen]
+impl WolframUniverse {
+    /// Create a universe; see `WolframUniverse::new`.
+    /// Nonzero entries in `seed_row` are live.
+    #[wasm_bindgen(js_name = new)]
+    pub fn new_js(
+        rule: u8,
+        width: usize,
+        height: usize,
+        seed_row: Option<Vec<u8>>,
+    ) -> Result<WolframUniverse, UniverseError> {
+        let seed_row = seed_row.map(|row| row.into_iter().map(|v| v != 0).collect());
+        WolframUniverse::new(rule, width, height, seed_row)
+    }
+
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    /// Compute the next generation, dropping the oldest if the history is full.
+    /// The row wraps around at its ends.
+    pub fn tick(&mut self) {
+        let last = self.rows.back().expect("history is never empty");
+        let next = (0..self.width)
+            .map(|x| {
+                let left = last[(x + self.width - 1) % self.width] as u8;
+                let center = last[x] as u8;
+                let right = last[(x + 1) % self.width] as u8;
+                let pattern = (left << 2) | (center << 1) | right;
+                (self.rule >> pattern) & 1 == 1
+            })
+            .collect();
+
+        if self.rows.len() == self.height {
+            self.rows.pop_front();
+        }
+        self.rows.push_back(next);
+    }
+
+    /// Render the full history, one pixel per cell.
+    pub fn render2d(&self, canvas: &CanvasRenderingContext2d) -> Result<(), JsValue> {
+        let pixels: Vec<u8> = (0..self.height)
+            .flat_map(|y| {
+                let row = self.rows.get(y);
+                (0..self.width).map(move |x| match row {
+                    Some(row) if row[x] => Cell::Alive,
+                    _ => Cell::Dead,
+                })
+            })
+            .flat_map(|cell| (cell as u32).to_be_bytes())
+            .collect();
+        let data = ImageData::new_with_u8_clamped_array_and_sh(
+            wasm_bindgen::Clamped(&pixels),
+            self.width as u32,
+            self.height as u32,
+        )?;
+        canvas.put_image_data(&data, 0.0, 0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The first `generations` rows of `rule` grown from a single live cell,
+    /// drawn with `#` for live cells.
+    fn history(rule: u8, generations: usize) -> Vec<String> {
+        let mut universe = WolframUniverse::new(rule, 11, generations, None).unwrap();
+        for _ in 1..generations {
+            universe.tick();
+        }
+        universe
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .map(|&live| if live { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rule_30() {
+        assert_eq!(
+            history(30, 5),
+            [
+                ".....#.....",
+                "....###....",
+                "...##..#...",
+                "..##.####..",
+                ".##..#...#.",
+            ]
+        );
+    }
+
+    #[test]
+    fn rule_90() {
+        assert_eq!(
+            history(90, 5),
+            [
+                ".....#.....",
+                "....#.#....",
+                "...#...#...",
+                "..#.#.#.#..",
+                ".#.......#.",
+            ]
+        );
+    }
+
+    #[test]
+    fn rule_110() {
+        assert_eq!(
+            history(110, 5),
+            [
+                ".....#.....",
+                "....##.....",
+                "...###.....",
+                "..##.#.....",
+                ".#####.....",
+            ]
+        );
+    }
+
+    #[test]
+    fn history_is_limited_to_height() {
+        let mut universe = WolframUniverse::new(30, 11, 3, None).unwrap();
+        for _ in 0..10 {
+            universe.tick();
+        }
+        assert_eq!(universe.rows().count(), 3);
+    }
+
+    #[test]
+    fn seed_row_is_padded_to_width() {
+        let universe = WolframUniverse::new(30, 4, 1, Some(vec![true])).unwrap();
+        assert_eq!(
+            universe.rows().next().unwrap(),
+            &vec![true, false, false, false]
+        );
+    }
+
+    #[test]
+    fn new_rejects_empty_dimensions() {
+        assert!(WolframUniverse::new(30, 0, 10, None).is_err());
+        assert!(WolframUniverse::new(30, 10, 0, None).is_err());
+    }
+}